@@ -1,6 +1,10 @@
-use iced::widget::{container, text};
-use iced::{Element, Length, Point, Sandbox, Settings};
-use iced_node_editor::{connection, graph_container, node, Matrix};
+use iced::widget::{button, column, container, row, slider, text};
+use iced::{Element, Length, Point, Sandbox, Settings, Size};
+use iced_node_editor::layout::{force_directed, layered, ForceOptions, LayeredOptions};
+use iced_node_editor::{
+    connection, graph_container, node, port, ConnectionRouting, DragRegion, Endpoint, Flow,
+    GraphEvent, Marker, Matrix, Mode, RouteCache, Status,
+};
 
 pub fn main() -> iced::Result {
     // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
@@ -35,20 +39,33 @@ pub fn main() -> iced::Result {
 
 struct NodeState {
     position: Point,
+    size: Size,
+    /// Size the node is laid out with, changed by resizing it.
+    dimensions: Size,
     text: String,
+    selected: bool,
+    collapsed: bool,
+    gain: f32,
+    mode: Mode,
 }
 
 struct Example {
     matrix: Matrix,
     nodes: Vec<NodeState>,
     connections: Vec<(usize, usize)>,
+    route_cache: RouteCache,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum Message {
-    ScaleChanged(f32, f32, f32),
-    TranslationChanged(f32, f32),
-    MoveNode(usize, f32, f32),
+    Graph(GraphEvent<usize>),
+    Arrange,
+    Relax,
+    RemoveConnection(usize),
+    Collapse(usize, bool),
+    Resize(usize, Size),
+    Gain(usize, f32),
+    SetMode(Mode),
 }
 
 impl Sandbox for Example {
@@ -61,17 +78,36 @@ impl Sandbox for Example {
                 NodeState {
                     position: Point::new(0.0, 0.0),
                     text: String::from("Iced"),
+                    size: Size::ZERO,
+                    dimensions: Size::new(200.0, 110.0),
+                    selected: false,
+                    collapsed: false,
+                    gain: 0.5,
+                    mode: Mode::Active,
                 },
                 NodeState {
                     position: Point::new(250.0, 250.0),
                     text: String::from("Node"),
+                    size: Size::ZERO,
+                    dimensions: Size::new(200.0, 110.0),
+                    selected: false,
+                    collapsed: false,
+                    gain: 0.5,
+                    mode: Mode::Active,
                 },
                 NodeState {
                     position: Point::new(500.0, 250.0),
                     text: String::from("Editor"),
+                    size: Size::ZERO,
+                    dimensions: Size::new(200.0, 110.0),
+                    selected: false,
+                    collapsed: false,
+                    gain: 0.5,
+                    mode: Mode::Active,
                 },
             ],
             connections: vec![(0, 1), (1, 2)],
+            route_cache: RouteCache::new(),
         }
     }
 
@@ -83,63 +119,152 @@ impl Sandbox for Example {
         iced::Theme::Dark
     }
 
-    fn update(&mut self, _message: Message) {
-        match _message {
-            Message::ScaleChanged(x, y, scale) => {
-                self.matrix = self
-                    .matrix
-                    .translate(-x, -y)
-                    .scale(if scale > 0.0 { 1.2 } else { 1.0 / 1.2 })
-                    .translate(x, y);
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Graph(GraphEvent::CameraChanged(matrix)) => self.matrix = matrix,
+            Message::Graph(GraphEvent::NodeMoved { id, delta }) => {
+                self.nodes[id].position = self.nodes[id].position + delta;
             }
-            Message::TranslationChanged(x, y) => self.matrix = self.matrix.translate(x, y),
-            Message::MoveNode(i, x, y) => {
-                self.nodes[i].position =
-                    Point::new(self.nodes[i].position.x + x / self.matrix.get_scale(), self.nodes[i].position.y + y / self.matrix.get_scale());
+            Message::Graph(GraphEvent::SelectionChanged(selection)) => {
+                for (i, n) in self.nodes.iter_mut().enumerate() {
+                    n.selected = selection.contains(&i);
+                }
+            }
+            Message::Graph(GraphEvent::NodesMeasured(sizes)) => {
+                for (id, size) in sizes {
+                    self.nodes[id].size = size;
+                }
+            }
+            Message::Graph(GraphEvent::ModesRequested(modes)) => {
+                for (id, mode) in modes {
+                    self.nodes[id].mode = mode;
+                }
+            }
+            Message::Graph(_) => {}
+            Message::Arrange => {
+                let nodes: Vec<(usize, Size)> =
+                    self.nodes.iter().map(|n| n.size).enumerate().collect();
+                let positions = layered(&nodes, &self.connections, &LayeredOptions::default());
+
+                for (id, position) in positions {
+                    self.nodes[id].position = position;
+                }
+            }
+            Message::Relax => {
+                let nodes: Vec<(usize, Point, Size)> = self
+                    .nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, n)| (i, n.position, n.size))
+                    .collect();
+                let pinned: Vec<usize> = (0..self.nodes.len())
+                    .filter(|i| self.nodes[*i].selected)
+                    .collect();
+                let positions =
+                    force_directed(&nodes, &self.connections, &pinned, ForceOptions::default());
+
+                for (id, position) in positions {
+                    self.nodes[id].position = position;
+                }
+            }
+            Message::RemoveConnection(index) => {
+                self.connections.remove(index);
+            }
+            Message::Collapse(id, collapsed) => {
+                self.nodes[id].collapsed = collapsed;
+            }
+            Message::Resize(id, size) => {
+                self.nodes[id].dimensions = size;
+            }
+            Message::Gain(id, gain) => {
+                self.nodes[id].gain = gain;
+            }
+            Message::SetMode(mode) => {
+                for n in self.nodes.iter_mut().filter(|n| n.selected) {
+                    n.mode = mode;
+                }
             }
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let mut graph_content = Vec::new();
 
         for (i, n) in self.nodes.iter().enumerate() {
+            let status = if n.gain > 0.9 {
+                Status::Warning
+            } else {
+                Status::Idle
+            };
+
             graph_content.push(
-                node(text(&n.text))
+                node(text(format!("#{i}")))
+                    .title(text(&n.text).size(14))
+                    .input(port(text("in").size(12)))
+                    .input(
+                        port(text("gain").size(12))
+                            .editor(
+                                slider(0.0..=1.0, n.gain, move |gain| Message::Gain(i, gain))
+                                    .step(0.01)
+                                    .width(Length::Fixed(80.0)),
+                            )
+                            .tooltip(text(format!("Gain: {:.2}", n.gain)).size(12)),
+                    )
+                    .output(port(text("out").size(12)))
+                    .collapsed(n.collapsed)
+                    .on_collapse(move |collapsed| Message::Collapse(i, collapsed))
+                    .drag_region(DragRegion::Header)
                     .center_x()
                     .center_y()
-                    .on_translate(move |p| Message::MoveNode(i, p.0, p.1))
-                    .width(Length::Fixed(200.0))
-                    .height(Length::Fixed(75.0))
+                    .id(i)
+                    .selected(n.selected)
+                    .on_resize(move |size| Message::Resize(i, size))
+                    .status(status)
+                    .mode(n.mode)
+                    .status_message(text("Gain above 0.9 may clip").size(12))
+                    .tooltip(text(format!("Node #{i}: {}", n.text)).size(12))
+                    .width(Length::Fixed(n.dimensions.width))
+                    .height(Length::Fixed(n.dimensions.height))
                     .position(n.position)
                     .into(),
             );
         }
 
-        for (_i, c) in self.connections.iter().enumerate() {
-            graph_content.push(
-                connection(
-                    Point::new(
-                        self.nodes[c.0].position.x + 200.0,
-                        self.nodes[c.0].position.y + 37.5,
-                    ),
-                    Point::new(
-                        self.nodes[c.1].position.x,
-                        self.nodes[c.1].position.y + 37.5,
-                    ),
-                )
-                .into(),
-            );
+        for (i, c) in self.connections.iter().enumerate() {
+            let mut wire = connection(Endpoint::Output(c.0, 0), Endpoint::Input(c.1, 0))
+                .end_marker(Marker::Arrow)
+                .on_double_click(Message::RemoveConnection(i))
+                .label(text(format!("{} → {}", c.0, c.1)).size(12.0 * self.matrix.get_scale()))
+                .tooltip(text("Double click to remove").size(12));
+
+            if self.nodes[c.0].selected {
+                wire = wire.flow(Flow::Dashes {
+                    speed: 40.0,
+                    spacing: 16.0,
+                });
+            }
+
+            graph_content.push(wire.into());
         }
 
-        container(
+        container(column![
+            row![
+                button("Arrange").on_press(Message::Arrange),
+                button("Relax").on_press(Message::Relax),
+                button("Activate").on_press(Message::SetMode(Mode::Active)),
+                button("Mute").on_press(Message::SetMode(Mode::Muted)),
+                button("Bypass").on_press(Message::SetMode(Mode::Bypassed)),
+            ]
+            .spacing(5),
             graph_container(graph_content)
-                .on_translate(|p| Message::TranslationChanged(p.0, p.1))
-                .on_scale(|x, y, s| Message::ScaleChanged(x, y, s))
+                .routing(ConnectionRouting::AroundNodes { smooth: true })
+                .route_cache(&self.route_cache)
+                .raise_selected(true)
+                .on_event(Message::Graph)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .matrix(self.matrix),
-        )
+        ])
         .width(Length::Fill)
         .height(Length::Fill)
         .into()
@@ -1,44 +1,128 @@
 use std::sync::Mutex;
 
 use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
+use iced::advanced::mouse;
 use iced::advanced::renderer;
-use iced::{advanced::Widget, Length, Point, Size, Vector};
+use iced::advanced::widget::tree;
+use iced::advanced::{layout, Layout, Widget};
+use iced::time::Instant;
+use iced::window::{self, RedrawRequest};
+use iced::{Color, Element, Length, Point, Rectangle, Size, Vector};
 
 use crate::{
     mesh_renderer::MeshRenderer,
-    node_element::{GraphNodeElement, ScalableWidget},
-    styles::connection::StyleSheet,
+    node_element::{GraphNodeElement, LayoutContext, ScalableWidget},
+    port::Endpoint,
+    routing::{route, ConnectionRouting, Sampling},
+    styles::connection::{StrokePattern, StyleSheet},
+    tessellation::{disc, stroke_polyline, LineCap, LineJoin, Pen, Tessellation, FEATHER},
+    tooltip::{self, TooltipStyle},
 };
 
-pub struct Connection<Message, Renderer>
+/// A shape drawn at one end of a [`Connection`], pointing away from the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Marker {
+    #[default]
+    None,
+    Arrow,
+    OpenArrow,
+    Circle,
+    Diamond,
+    Bar,
+}
+
+/// An animation showing data moving along a [`Connection`], from its source to its target.
+/// Speeds are in graph units per second and spacings in graph units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow {
+    /// Dashes marching along the wire, one dash and one gap per `spacing`.
+    Dashes { speed: f32, spacing: f32 },
+    /// Round pulses traveling on top of the wire.
+    Pulses { speed: f32, spacing: f32 },
+}
+
+/// Length of a marker relative to the width of the wire.
+const MARKER_SCALE: f32 = 6.0;
+
+type HoverHandler<'a, Message> = Box<dyn Fn(bool) -> Message + 'a>;
+
+pub struct Connection<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
-    from: Point,
-    to: Point,
+    from: Endpoint<Id>,
+    to: Endpoint<Id>,
     width: f32,
-    number_of_segments: usize,
+    number_of_segments: Option<usize>,
+    tolerance: f32,
+    line_join: LineJoin,
+    line_cap: LineCap,
+    start_marker: Marker,
+    end_marker: Marker,
+    gradient: Option<(Color, Color)>,
+    flow: Option<Flow>,
+    routing: Option<ConnectionRouting>,
+    hit_tolerance: f32,
+    on_click: Option<Message>,
+    on_double_click: Option<Message>,
+    on_hover: Option<HoverHandler<'a, Message>>,
+    label: Option<Element<'a, Message, Renderer>>,
+    label_position: f32,
+    tooltip: Option<Element<'a, Message, Renderer>>,
     style: <Renderer::Theme as StyleSheet>::Style,
+    z_index: i32,
+
+    geometry: Mutex<Geometry>,
+}
 
-    phantom_message: std::marker::PhantomData<Message>,
-    spline: Mutex<Vec<Vector>>,
+#[derive(Default)]
+struct State {
+    started: Option<Instant>,
+    now: Option<Instant>,
+    hovered: bool,
+    last_click: Option<mouse::Click>,
 }
 
-impl<Message, Renderer> Connection<Message, Renderer>
+/// Tessellation inputs computed during layout, relative to the layout bounds.
+#[derive(Default)]
+struct Geometry {
+    scale: f32,
+    spline: Vec<Vector>,
+    /// Arc length of `spline`, along which gradients run.
+    length: f32,
+    markers: Tessellation,
+}
+
+impl<'a, Id, Message, Renderer> Connection<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
-    pub fn new(from: Point, to: Point) -> Self {
+    pub fn new(from: impl Into<Endpoint<Id>>, to: impl Into<Endpoint<Id>>) -> Self {
         Connection {
-            spline: Mutex::new(Vec::new()),
-            from,
-            to,
+            geometry: Mutex::new(Geometry::default()),
+            from: from.into(),
+            to: to.into(),
             width: 1.2,
-            number_of_segments: 20,
-            phantom_message: std::marker::PhantomData,
+            number_of_segments: None,
+            tolerance: 0.25,
+            line_join: LineJoin::default(),
+            line_cap: LineCap::default(),
+            start_marker: Marker::None,
+            end_marker: Marker::None,
+            gradient: None,
+            flow: None,
+            routing: None,
+            hit_tolerance: 4.0,
+            on_click: None,
+            on_double_click: None,
+            on_hover: None,
+            label: None,
+            label_position: 0.5,
+            tooltip: None,
             style: Default::default(),
+            z_index: 0,
         }
     }
 
@@ -47,13 +131,144 @@ where
         self
     }
 
+    /// Samples curves at a fixed number of points instead of adapting to the zoom level.
     pub fn number_of_segments(mut self, number_of_segments: usize) -> Self {
-        self.number_of_segments = number_of_segments;
+        self.number_of_segments = Some(number_of_segments);
+        self
+    }
+
+    /// Greatest distance in pixels allowed between a curve and the polyline drawn for it.
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    pub fn line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    pub fn start_marker(mut self, marker: Marker) -> Self {
+        self.start_marker = marker;
+        self
+    }
+
+    pub fn end_marker(mut self, marker: Marker) -> Self {
+        self.end_marker = marker;
+        self
+    }
+
+    /// Blends the wire from `start` at its source to `end` at its target along
+    /// its length, typically the colors of the two ports it links.
+    pub fn gradient(mut self, start: Color, end: Color) -> Self {
+        self.gradient = Some((start, end));
+        self
+    }
+
+    pub fn flow(mut self, flow: Flow) -> Self {
+        self.flow = Some(flow);
+        self
+    }
+
+    /// Overrides the routing set on the [`GraphContainer`](crate::GraphContainer).
+    pub fn routing(mut self, routing: ConnectionRouting) -> Self {
+        self.routing = Some(routing);
+        self
+    }
+
+    /// Distance in pixels from the edge of the wire within which it is still hovered.
+    pub fn hit_tolerance(mut self, hit_tolerance: f32) -> Self {
+        self.hit_tolerance = hit_tolerance;
+        self
+    }
+
+    pub fn on_click(mut self, message: Message) -> Self {
+        self.on_click = Some(message);
+        self
+    }
+
+    pub fn on_double_click(mut self, message: Message) -> Self {
+        self.on_double_click = Some(message);
+        self
+    }
+
+    /// Called with `true` when the cursor enters the wire and `false` when it leaves.
+    pub fn on_hover(mut self, f: impl Fn(bool) -> Message + 'a) -> Self {
+        self.on_hover = Some(Box::new(f));
+        self
+    }
+
+    /// Shows `label` centered on the wire, moving with the graph.
+    ///
+    /// The label is not scaled with the graph: iced has no way to resize the
+    /// contents of a widget, so buttons, text inputs and text keep the same size at
+    /// every zoom level. Text can still follow the zoom when given a size multiplied
+    /// by [`Matrix::get_scale`](crate::Matrix::get_scale).
+    pub fn label(mut self, label: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Where the label sits along the wire, from 0 at its source to 1 at its target.
+    pub fn label_position(mut self, position: f32) -> Self {
+        self.label_position = position.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Shows `tooltip` next to the cursor while the wire is hovered, after the delay of
+    /// the [`GraphContainer`](crate::GraphContainer).
+    pub fn tooltip(mut self, tooltip: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// The label and the tooltip, in the order of their trees.
+    fn elements(&self) -> Vec<&Element<'a, Message, Renderer>> {
+        self.label.iter().chain(self.tooltip.iter()).collect()
+    }
+
+    fn hit(&self, bounds: iced::Rectangle, position: Point) -> bool {
+        let tolerance = self.hit_tolerance + self.width / 2.0;
+        let expanded = iced::Rectangle {
+            x: bounds.x - tolerance,
+            y: bounds.y - tolerance,
+            width: bounds.width + tolerance * 2.0,
+            height: bounds.height + tolerance * 2.0,
+        };
+        if !expanded.contains(position) {
+            return false;
+        }
+
+        let point = Vector::new(position.x - bounds.x, position.y - bounds.y);
+        let geometry = self.geometry.lock().unwrap();
+        geometry
+            .spline
+            .windows(2)
+            .any(|segment| distance_to_segment(point, segment[0], segment[1]) <= tolerance)
+    }
+
+    pub fn style(mut self, style: impl Into<<Renderer::Theme as StyleSheet>::Style>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Connections with a higher z-index are drawn above the other elements and get
+    /// events first.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
         self
     }
 }
 
-pub fn connection<'a, Message, Renderer>(from: Point, to: Point) -> Connection<Message, Renderer>
+pub fn connection<'a, Id, Message, Renderer>(
+    from: impl Into<Endpoint<Id>>,
+    to: impl Into<Endpoint<Id>>,
+) -> Connection<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -61,48 +276,254 @@ where
     Connection::new(from, to)
 }
 
-impl<'a, Message, Renderer> ScalableWidget<Message, Renderer> for Connection<Message, Renderer>
+impl<'a, Id, Message, Renderer> ScalableWidget<Id, Message, Renderer>
+    for Connection<'a, Id, Message, Renderer>
 where
+    Id: PartialEq,
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
     fn layout(
         &self,
-        _renderer: &Renderer,
-        _limits: &iced::advanced::layout::Limits,
-        scale: f32,
-    ) -> iced::advanced::layout::Node {
-        let spline = generate_spline(
-            Vector::new(self.from.x, self.from.y) * scale,
-            1.0,
-            Vector::new(self.to.x, self.to.y) * scale,
-            self.number_of_segments,
-            1.0_f32,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        context: &LayoutContext<'_, Id>,
+    ) -> layout::Node {
+        let scale = context.scale;
+        let (Some(from), Some(to)) = (self.from.resolve(context), self.to.resolve(context)) else {
+            // One of the nodes is missing or has no such port.
+            *self.geometry.lock().expect("Could not lock mutex") = Geometry::default();
+            return layout::Node::new(Size::ZERO);
+        };
+
+        let spline = route(
+            self.routing.unwrap_or(context.routing),
+            from,
+            to,
+            match self.number_of_segments {
+                Some(number_of_segments) => Sampling::Uniform(number_of_segments),
+                None => Sampling::Adaptive {
+                    tolerance: self.tolerance.max(0.01),
+                },
+            },
+            context,
         );
 
-        let spline_bounds = bounds_for_vectors(&spline);
+        let length = arc_length(&spline);
+        let mut markers = Tessellation::default();
+        if spline.len() >= 2 {
+            let last = spline.len() - 1;
+            let size = self.width * MARKER_SCALE;
 
-        let spline = spline
-            .iter()
-            .map(|p| Vector::new(p.x - spline_bounds.x, p.y - spline_bounds.y))
-            .collect();
+            for (marker, tip, previous, distance) in [
+                (self.start_marker, spline[0], spline[1], 0.0),
+                (self.end_marker, spline[last], spline[last - 1], length),
+            ] {
+                markers.append(
+                    marker_polygon(
+                        marker,
+                        tip,
+                        normalize_vector(tip - previous),
+                        size,
+                        self.width,
+                    )
+                    .offset_along(distance),
+                );
+            }
+        }
+
+        let outline: Vec<Vector> = spline.iter().chain(&markers.vertices).copied().collect();
+        let bounds = bounds_for_vectors(&outline);
+        let origin = Vector::new(bounds.x - self.width / 2.0, bounds.y - self.width / 2.0);
+
+        let mut geometry = self.geometry.lock().expect("Could not lock mutex");
+        geometry.scale = scale;
+        geometry.spline = spline.iter().map(|p| *p - origin).collect();
+        geometry.length = length;
+        markers.vertices.iter_mut().for_each(|p| *p = *p - origin);
+        geometry.markers = markers;
+
+        let size = Size::new(
+            (bounds.width + self.width).ceil(),
+            (bounds.height + self.width).ceil(),
+        );
 
-        let node = iced::advanced::layout::Node::new(Size::new(
-            (spline_bounds.width + self.width).ceil(),
-            (spline_bounds.height + self.width).ceil(),
-        ));
+        let node = match &self.label {
+            Some(label) => {
+                let mut label = label
+                    .as_widget()
+                    .layout(renderer, &layout::Limits::new(Size::ZERO, limits.max()));
+                let anchor = point_along(&geometry.spline, self.label_position);
+                label.move_to(Point::new(
+                    anchor.x - label.size().width / 2.0,
+                    anchor.y - label.size().height / 2.0,
+                ));
 
-        let mut self_state = self.spline.lock().expect("Could not lock mutex");
-        *self_state = spline;
+                layout::Node::with_children(size, vec![label])
+            }
+            None => layout::Node::new(size),
+        };
+
+        node.translate(origin)
+    }
+
+    fn endpoints(&self) -> Vec<&Endpoint<Id>> {
+        vec![&self.from, &self.to]
+    }
 
-        node.translate(Vector::new(spline_bounds.x, spline_bounds.y))
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn tooltip_at(&self, layout: Layout<'_>, position: Point) -> Option<usize> {
+        self.tooltip.as_ref()?;
+        self.hit(layout.bounds(), position)
+            .then(|| self.label.iter().count())
+    }
+
+    fn draw_tooltip(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: TooltipStyle,
+        _layout: Layout<'_>,
+        tooltip: usize,
+        position: Point,
+        bounds: Rectangle,
+    ) {
+        if let (Some(element), Some(tree)) = (&self.tooltip, tree.children.get(tooltip)) {
+            tooltip::draw(element, tree, renderer, theme, style, position, bounds);
+        }
     }
 }
 
-impl<'a, Message, Renderer> Widget<Message, Renderer> for Connection<Message, Renderer>
+impl<'a, Id, Message, Renderer> Connection<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer + MeshRenderer,
     Renderer::Theme: StyleSheet,
+{
+    fn draw_wire(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        layout: iced::advanced::Layout<'_>,
+    ) {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State>();
+        let style = if state.hovered {
+            theme.hovered(&self.style)
+        } else {
+            theme.appearance(&self.style)
+        };
+
+        let geometry = self.geometry.lock().unwrap();
+
+        let elapsed = match (state.started, state.now) {
+            (Some(started), Some(now)) => now.duration_since(started).as_secs_f32(),
+            _ => 0.0,
+        };
+
+        let (pattern, phase) = match self.flow {
+            Some(Flow::Dashes { speed, spacing }) => (
+                StrokePattern::Dashed(vec![spacing / 2.0, spacing / 2.0]),
+                elapsed * speed,
+            ),
+            _ => (style.pattern.clone(), 0.0),
+        };
+
+        let pen = |half_width: f32| Pen {
+            half_width,
+            join: self.line_join,
+            cap: self.line_cap,
+            feather: FEATHER,
+        };
+
+        let mut pulses = Indexed {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+        if let Some(Flow::Pulses { speed, spacing }) = self.flow {
+            append_polygon(
+                &mut pulses,
+                stroke(
+                    &geometry.spline,
+                    &StrokePattern::Dotted(spacing),
+                    &pen(self.width * 1.5),
+                    geometry.scale,
+                    elapsed * speed,
+                ),
+                style.flow_color.or(style.color).unwrap(),
+            );
+        }
+
+        let mut buffers = Indexed {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+
+        if let Some(outline_color) = style.outline_color {
+            if style.outline_width > 0.0 {
+                append_polygon(
+                    &mut buffers,
+                    stroke(
+                        &geometry.spline,
+                        &pattern,
+                        &pen(self.width / 2.0 + style.outline_width),
+                        geometry.scale,
+                        phase,
+                    ),
+                    outline_color,
+                );
+            }
+        }
+
+        let wire = stroke(
+            &geometry.spline,
+            &pattern,
+            &pen(self.width / 2.0),
+            geometry.scale,
+            phase,
+        );
+
+        match self.gradient {
+            Some((start, end)) => {
+                append_gradient(&mut buffers, wire, start, end, geometry.length);
+                match style.marker_color {
+                    Some(color) => append_polygon(&mut buffers, geometry.markers.clone(), color),
+                    None => append_gradient(
+                        &mut buffers,
+                        geometry.markers.clone(),
+                        start,
+                        end,
+                        geometry.length,
+                    ),
+                }
+            }
+            None => {
+                let color = style.color.unwrap();
+                append_polygon(&mut buffers, wire, color);
+                append_polygon(
+                    &mut buffers,
+                    geometry.markers.clone(),
+                    style.marker_color.unwrap_or(color),
+                );
+            }
+        }
+
+        renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
+            renderer.draw_buffers(buffers);
+            renderer.draw_buffers(pulses);
+        });
+    }
+}
+
+impl<'a, Id, Message, Renderer> Widget<Message, Renderer> for Connection<'a, Id, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: renderer::Renderer + MeshRenderer,
+    Renderer::Theme: StyleSheet,
 {
     fn layout(
         &self,
@@ -112,148 +533,515 @@ where
         todo!("This should never be called.")
     }
 
-    fn draw(
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn children(&self) -> Vec<iced::advanced::widget::Tree> {
+        self.elements()
+            .into_iter()
+            .map(iced::advanced::widget::Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut iced::advanced::widget::Tree) {
+        tree.diff_children(&self.elements());
+    }
+
+    fn operate(
         &self,
-        _tree: &iced::advanced::widget::Tree,
-        renderer: &mut Renderer,
-        theme: &<Renderer as iced::advanced::Renderer>::Theme,
-        _renderer_style: &renderer::Style,
+        tree: &mut iced::advanced::widget::Tree,
         layout: iced::advanced::Layout<'_>,
-        _cursor: iced::mouse::Cursor,
-        _viewport: &iced::Rectangle,
+        renderer: &Renderer,
+        operation: &mut dyn iced::advanced::widget::Operation<Message>,
     ) {
-        let bounds = layout.bounds();
-        let style = theme.appearance(&self.style);
+        if let (Some(label), Some(label_layout)) = (&self.label, layout.children().next()) {
+            label
+                .as_widget()
+                .operate(&mut tree.children[0], label_layout, renderer, operation);
+        }
+    }
 
-        let spline = self.spline.lock().unwrap();
-        let (vertices, indices) = line_to_polygon(&spline, self.width / 2.0);
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
 
-        let buffers = Indexed {
-            vertices: vertices
-                .iter()
-                .map(|p| SolidVertex2D {
-                    position: [p.x, p.y],
-                    color: iced::advanced::graphics::color::pack(style.color.unwrap()),
-                })
-                .collect(),
-            indices,
+    fn on_event(
+        &mut self,
+        tree: &mut iced::advanced::widget::Tree,
+        event: iced::Event,
+        layout: iced::advanced::Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn iced::advanced::Clipboard,
+        shell: &mut iced::advanced::Shell<'_, Message>,
+        viewport: &iced::Rectangle,
+    ) -> iced::event::Status {
+        if let (Some(label), Some(label_layout)) = (&mut self.label, layout.children().next()) {
+            let status = label.as_widget_mut().on_event(
+                &mut tree.children[0],
+                event.clone(),
+                label_layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+            if status == iced::event::Status::Captured {
+                return status;
+            }
+        }
+
+        let state = tree.state.downcast_mut::<State>();
+
+        if let iced::Event::Window(window::Event::RedrawRequested(now)) = event {
+            if self.flow.is_some() {
+                state.started.get_or_insert(now);
+                state.now = Some(now);
+
+                shell.request_redraw(RedrawRequest::NextFrame);
+            }
+            return iced::event::Status::Ignored;
+        }
+
+        let position = cursor
+            .position()
+            .filter(|position| self.hit(layout.bounds(), *position));
+
+        if position.is_some() != state.hovered {
+            state.hovered = position.is_some();
+            if let Some(on_hover) = &self.on_hover {
+                shell.publish(on_hover(state.hovered));
+            }
+        }
+
+        let Some(position) = position else {
+            return iced::event::Status::Ignored;
         };
 
-        renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
-            renderer.draw_buffers(buffers);
-        });
+        match event {
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if self.on_click.is_some() || self.on_double_click.is_some() =>
+            {
+                let click = mouse::Click::new(position, state.last_click);
+                state.last_click = Some(click);
+
+                let message = match click.kind() {
+                    mouse::click::Kind::Double if self.on_double_click.is_some() => {
+                        &self.on_double_click
+                    }
+                    _ => &self.on_click,
+                };
+                if let Some(message) = message {
+                    shell.publish(message.clone());
+                }
+
+                iced::event::Status::Captured
+            }
+            // Wires underneath stop being hovered.
+            iced::Event::Mouse(mouse::Event::CursorMoved { .. }) => iced::event::Status::Captured,
+            _ => iced::event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        layout: iced::advanced::Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &iced::Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if let (Some(label), Some(label_layout)) = (&self.label, layout.children().next()) {
+            let interaction = label.as_widget().mouse_interaction(
+                &tree.children[0],
+                label_layout,
+                cursor,
+                viewport,
+                renderer,
+            );
+            if interaction != mouse::Interaction::default() {
+                return interaction;
+            }
+        }
+
+        let state = tree.state.downcast_ref::<State>();
+
+        if state.hovered && (self.on_click.is_some() || self.on_double_click.is_some()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        renderer: &mut Renderer,
+        theme: &<Renderer as iced::advanced::Renderer>::Theme,
+        renderer_style: &renderer::Style,
+        layout: iced::advanced::Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &iced::Rectangle,
+    ) {
+        self.draw_wire(tree, renderer, theme, layout);
+
+        if let (Some(label), Some(label_layout)) = (&self.label, layout.children().next()) {
+            label.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                renderer_style,
+                label_layout,
+                cursor,
+                viewport,
+            );
+        }
     }
 
     fn width(&self) -> Length {
-        Length::Fixed((self.from.x - self.to.x).abs() + self.width)
+        match (&self.from, &self.to) {
+            (Endpoint::Point(from), Endpoint::Point(to)) => {
+                Length::Fixed((from.x - to.x).abs() + self.width)
+            }
+            _ => Length::Shrink,
+        }
     }
 
     fn height(&self) -> Length {
-        Length::Fixed((self.from.y - self.to.y).abs() + self.width)
+        match (&self.from, &self.to) {
+            (Endpoint::Point(from), Endpoint::Point(to)) => {
+                Length::Fixed((from.y - to.y).abs() + self.width)
+            }
+            _ => Length::Shrink,
+        }
     }
 }
 
-impl<'a, Message, Renderer> From<Connection<Message, Renderer>>
-    for GraphNodeElement<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> From<Connection<'a, Id, Message, Renderer>>
+    for GraphNodeElement<'a, Id, Message, Renderer>
 where
-    Message: 'a,
+    Id: PartialEq + 'a,
+    Message: Clone + 'a,
     Renderer: renderer::Renderer + MeshRenderer + 'a,
     Renderer::Theme: StyleSheet,
 {
-    fn from(node: Connection<Message, Renderer>) -> Self {
+    fn from(node: Connection<'a, Id, Message, Renderer>) -> Self {
         Self::new(node)
     }
 }
 
-fn line_to_polygon(points: &[Vector], width: f32) -> (Vec<Vector>, Vec<u32>) {
-    let mut result = Vec::new();
-    let mut indices = Vec::new();
+/// Appends `tessellation` in `color`, fading its alpha by the coverage of each vertex.
+fn append_polygon(
+    buffers: &mut Indexed<SolidVertex2D>,
+    tessellation: Tessellation,
+    color: iced::Color,
+) {
+    let offset = buffers.vertices.len() as u32;
 
-    let mut last = points[0];
-    for point in points.iter().skip(1) {
-        let dir = normalize_vector(*point - last);
-        let normal = Vector::new(dir.y, -dir.x);
+    buffers.vertices.extend(
+        tessellation
+            .vertices
+            .iter()
+            .zip(&tessellation.coverage)
+            .map(|(p, coverage)| SolidVertex2D {
+                position: [p.x, p.y],
+                color: iced::advanced::graphics::color::pack(Color {
+                    a: color.a * coverage,
+                    ..color
+                }),
+            }),
+    );
+    buffers
+        .indices
+        .extend(tessellation.indices.iter().map(|i| i + offset));
+}
 
-        result.push(last + normal * width);
-        result.push(*point + normal * width);
-        result.push(*point - normal * width);
-        result.push(last - normal * width);
+/// Appends `tessellation` colored from `start` to `end` along its arc length,
+/// `length` being where `end` is reached.
+fn append_gradient(
+    buffers: &mut Indexed<SolidVertex2D>,
+    tessellation: Tessellation,
+    start: Color,
+    end: Color,
+    length: f32,
+) {
+    let offset = buffers.vertices.len() as u32;
 
-        let start = result.len() as u32 - 4;
-        indices.push(start);
-        indices.push(start + 1);
-        indices.push(start + 2);
+    buffers.vertices.extend(
+        tessellation
+            .vertices
+            .iter()
+            .zip(&tessellation.coverage)
+            .zip(&tessellation.distances)
+            .map(|((p, coverage), distance)| {
+                let t = if length > 0.0 {
+                    (distance / length).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+
+                SolidVertex2D {
+                    position: [p.x, p.y],
+                    color: iced::advanced::graphics::color::pack(Color {
+                        r: start.r + (end.r - start.r) * t,
+                        g: start.g + (end.g - start.g) * t,
+                        b: start.b + (end.b - start.b) * t,
+                        a: (start.a + (end.a - start.a) * t) * coverage,
+                    }),
+                }
+            }),
+    );
+    buffers
+        .indices
+        .extend(tessellation.indices.iter().map(|i| i + offset));
+}
 
-        indices.push(start);
-        indices.push(start + 2);
-        indices.push(start + 3);
+/// Tessellates `points` with the given pattern, whose lengths are multiplied by `scale`.
+/// A positive `phase` moves the pattern that far towards the end of the wire.
+fn stroke(
+    points: &[Vector],
+    pattern: &StrokePattern,
+    pen: &Pen,
+    scale: f32,
+    phase: f32,
+) -> Tessellation {
+    let mut tessellation = Tessellation::default();
 
-        last = *point;
+    match pattern {
+        StrokePattern::Dashed(lengths)
+            if lengths.iter().all(|length| *length >= 0.0) && lengths.iter().sum::<f32>() > 0.0 =>
+        {
+            let lengths: Vec<f32> = lengths.iter().map(|length| length * scale).collect();
+            for (distance, dash) in dash_polyline(points, &lengths, -phase * scale) {
+                tessellation.append(stroke_polyline(&dash, pen).offset_along(distance));
+            }
+        }
+        StrokePattern::Dotted(spacing) if *spacing > 0.0 => {
+            let spacing = spacing * scale;
+            for (distance, dot) in dots_along(points, spacing, (phase * scale).rem_euclid(spacing))
+            {
+                tessellation.append(disc(dot, pen).offset_along(distance));
+            }
+        }
+        _ => tessellation.append(stroke_polyline(points, pen)),
     }
 
-    (result, indices)
+    tessellation
 }
 
-fn normalize_vector(vector: Vector) -> Vector {
-    let length = (vector.x * vector.x + vector.y * vector.y).sqrt();
-    if length == 0.0 {
-        Vector::new(0.0, 0.0)
-    } else {
-        Vector::new(vector.x / length, vector.y / length)
+/// Splits `points` into the "on" runs of a dash pattern measured along arc length,
+/// with the start of the polyline `offset` into the pattern. Every run comes with
+/// the arc length at which it starts.
+fn dash_polyline(points: &[Vector], pattern: &[f32], offset: f32) -> Vec<(f32, Vec<Vector>)> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut dashes = Vec::new();
+    let mut index = 0;
+    let mut on = true;
+    let mut remaining = pattern[0];
+
+    // Odd patterns swap dashes and gaps on every repetition.
+    let cycle = pattern.iter().sum::<f32>() * (2 - pattern.len() % 2) as f32;
+    let mut offset = offset.rem_euclid(cycle);
+    while offset >= remaining {
+        offset -= remaining;
+        on = !on;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    remaining -= offset;
+
+    let mut current = vec![points[0]];
+    let mut travelled = 0.0;
+    let mut dash_start = 0.0;
+
+    for pair in points.windows(2) {
+        let (mut start, end) = (pair[0], pair[1]);
+        let mut length = vector_length(end - start);
+
+        while length > remaining {
+            let split = start + (end - start) * (remaining / length);
+            travelled += remaining;
+            if on {
+                current.push(split);
+                dashes.push((dash_start, std::mem::take(&mut current)));
+            } else {
+                current = vec![split];
+                dash_start = travelled;
+            }
+
+            length -= remaining;
+            start = split;
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+
+        remaining -= length;
+        travelled += length;
+        if on {
+            current.push(end);
+        }
+    }
+
+    if on && current.len() >= 2 {
+        dashes.push((dash_start, current));
     }
+
+    dashes
 }
 
-fn dot_vector(vector: Vector, other: Vector) -> f32 {
-    vector.x * other.x + vector.y * other.y
+/// Points every `spacing` along the arc length of `points`, the first one `first` from the start,
+/// each with its arc length.
+fn dots_along(points: &[Vector], spacing: f32, first: f32) -> Vec<(f32, Vector)> {
+    let mut dots = Vec::new();
+    let mut travelled = 0.0;
+    let mut next = first;
+
+    for pair in points.windows(2) {
+        let length = vector_length(pair[1] - pair[0]);
+        if length <= 0.0 {
+            continue;
+        }
+
+        while travelled + length >= next {
+            dots.push((
+                next,
+                pair[0] + (pair[1] - pair[0]) * ((next - travelled) / length),
+            ));
+            next += spacing;
+        }
+        travelled += length;
+    }
+
+    dots
 }
 
-fn generate_spline(
-    from: Vector,
-    control_scale: f32,
-    to: Vector,
-    number_of_segments: usize,
-    alpha: f32,
-) -> Vec<Vector> {
-    let mut spline = Vec::new();
-
-    for i in 0..number_of_segments {
-        let t = i as f32 / (number_of_segments - 1) as f32;
-        let p = catmull_rom(
-            Vector::new(from.x - control_scale, from.y),
-            from,
-            to,
-            Vector::new(to.x + control_scale, to.y),
-            t,
-            alpha,
-        );
-        spline.push(p);
+/// Builds the triangles of `marker` with its tip at `tip`, pointing along `direction`.
+fn marker_polygon(
+    marker: Marker,
+    tip: Vector,
+    direction: Vector,
+    size: f32,
+    width: f32,
+) -> Tessellation {
+    let normal = Vector::new(direction.y, -direction.x);
+
+    match marker {
+        Marker::None => Tessellation::default(),
+        Marker::Arrow => Tessellation::polygon(
+            vec![
+                tip,
+                tip - direction * size + normal * (size * 0.4),
+                tip - direction * size - normal * (size * 0.4),
+            ],
+            vec![0, 1, 2],
+        ),
+        Marker::OpenArrow => stroke_polyline(
+            &[
+                tip - direction * size + normal * (size * 0.4),
+                tip,
+                tip - direction * size - normal * (size * 0.4),
+            ],
+            &Pen {
+                half_width: width / 2.0,
+                join: LineJoin::Miter,
+                cap: LineCap::Butt,
+                feather: FEATHER,
+            },
+        ),
+        Marker::Circle => {
+            let radius = size * 0.35;
+            disc(
+                tip - direction * radius,
+                &Pen {
+                    half_width: radius,
+                    join: LineJoin::Round,
+                    cap: LineCap::Round,
+                    feather: FEATHER,
+                },
+            )
+        }
+        Marker::Diamond => Tessellation::polygon(
+            vec![
+                tip,
+                tip - direction * (size * 0.5) + normal * (size * 0.35),
+                tip - direction * size,
+                tip - direction * (size * 0.5) - normal * (size * 0.35),
+            ],
+            vec![0, 1, 2, 0, 2, 3],
+        ),
+        Marker::Bar => {
+            let center = tip - direction * width;
+            let along = direction * (width * 0.75);
+            let across = normal * (size * 0.5);
+            Tessellation::polygon(
+                vec![
+                    center + along + across,
+                    center - along + across,
+                    center - along - across,
+                    center + along - across,
+                ],
+                vec![0, 1, 2, 0, 2, 3],
+            )
+        }
     }
+}
+
+/// The point `t` of the way along the arc length of `points`.
+fn point_along(points: &[Vector], t: f32) -> Vector {
+    let total: f32 = points
+        .windows(2)
+        .map(|pair| vector_length(pair[1] - pair[0]))
+        .sum();
+    let mut remaining = total * t;
 
-    spline
+    for pair in points.windows(2) {
+        let length = vector_length(pair[1] - pair[0]);
+        if length > 0.0 && remaining <= length {
+            return pair[0] + (pair[1] - pair[0]) * (remaining / length);
+        }
+        remaining -= length;
+    }
+
+    points.last().copied().unwrap_or(Vector::new(0.0, 0.0))
+}
+
+fn distance_to_segment(point: Vector, start: Vector, end: Vector) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.x * segment.x + segment.y * segment.y;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.x - start.x) * segment.x + (point.y - start.y) * segment.y) / length_squared)
+            .clamp(0.0, 1.0)
+    };
+
+    vector_length(point - (start + segment * t))
 }
 
-// Code taken and adapted from https://en.wikipedia.org/wiki/Centripetal_Catmull%E2%80%93Rom_spline
-fn get_t(t: f32, alpha: f32, p0: Vector, p1: Vector) -> f32 {
-    let d = p1 - p0;
-    let a = dot_vector(d, d);
-    let b = a.powf(alpha * 0.5);
-    b + t
+fn vector_length(vector: Vector) -> f32 {
+    (vector.x * vector.x + vector.y * vector.y).sqrt()
 }
 
-fn catmull_rom(p0: Vector, p1: Vector, p2: Vector, p3: Vector, t: f32, alpha: f32) -> Vector {
-    let t0 = 0.0;
-    let t1 = get_t(t0, alpha, p0, p1);
-    let t2 = get_t(t1, alpha, p1, p2);
-    let t3 = get_t(t2, alpha, p2, p3);
-    let t = t1 + (t2 - t1) * t;
-    let a1 = p0 * ((t1 - t) / (t1 - t0)) + p1 * ((t - t0) / (t1 - t0));
-    let a2 = p1 * ((t2 - t) / (t2 - t1)) + p2 * ((t - t1) / (t2 - t1));
-    let a3 = p2 * ((t3 - t) / (t3 - t2)) + p3 * ((t - t2) / (t3 - t2));
-    let b1 = a1 * ((t2 - t) / (t2 - t0)) + a2 * ((t - t0) / (t2 - t0));
-    let b2 = a2 * ((t3 - t) / (t3 - t1)) + a3 * ((t - t1) / (t3 - t1));
-    let c = b1 * ((t2 - t) / (t2 - t1)) + b2 * ((t - t1) / (t2 - t1));
-
-    c
+fn arc_length(points: &[Vector]) -> f32 {
+    points
+        .windows(2)
+        .map(|pair| vector_length(pair[1] - pair[0]))
+        .sum()
+}
+
+fn normalize_vector(vector: Vector) -> Vector {
+    let length = vector_length(vector);
+    if length == 0.0 {
+        Vector::new(0.0, 0.0)
+    } else {
+        Vector::new(vector.x / length, vector.y / length)
+    }
 }
 
 fn bounds_for_vectors(points: &[Vector]) -> iced::Rectangle {
@@ -284,3 +1072,118 @@ fn bounds_for_vectors(points: &[Vector]) -> iced::Rectangle {
         height: max_y - min_y,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pen() -> Pen {
+        Pen {
+            half_width: 2.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            feather: FEATHER,
+        }
+    }
+
+    #[test]
+    fn distances_follow_the_arc_length_of_s_curves() {
+        // Goes right, back left below and right again, like an S on its side.
+        let points = [
+            Vector::new(0.0, 0.0),
+            Vector::new(100.0, 0.0),
+            Vector::new(100.0, 50.0),
+            Vector::new(0.0, 50.0),
+            Vector::new(0.0, 100.0),
+            Vector::new(100.0, 100.0),
+        ];
+        let length = arc_length(&points);
+        assert_eq!(length, 400.0);
+
+        let tessellation = stroke(&points, &StrokePattern::Solid, &pen(), 1.0, 0.0);
+        assert!(tessellation
+            .distances
+            .windows(2)
+            .all(|pair| pair[0] <= pair[1]));
+        assert_eq!(tessellation.distances.last(), Some(&length));
+
+        // The corner where the wire turns back left is 250 along it, although
+        // its x would put it at the very start of a straight endpoint gradient.
+        let corner = tessellation
+            .vertices
+            .iter()
+            .zip(&tessellation.distances)
+            .find(|(p, _)| (p.x - 0.0).abs() < 3.0 && (p.y - 50.0).abs() < 3.0)
+            .map(|(_, distance)| *distance);
+        assert_eq!(corner, Some(250.0));
+    }
+
+    #[test]
+    fn dashes_and_dots_keep_their_place_along_the_wire() {
+        let points = [Vector::new(0.0, 0.0), Vector::new(100.0, 0.0)];
+
+        let dashes = stroke(
+            &points,
+            &StrokePattern::Dashed(vec![10.0, 10.0]),
+            &pen(),
+            1.0,
+            0.0,
+        );
+        for (p, distance) in dashes.vertices.iter().zip(&dashes.distances) {
+            assert!((p.x - distance).abs() < 1e-3);
+        }
+
+        let dots = stroke(&points, &StrokePattern::Dotted(25.0), &pen(), 1.0, 0.0);
+        let mut centers: Vec<f32> = dots.distances.clone();
+        centers.dedup();
+        assert_eq!(centers, [0.0, 25.0, 50.0, 75.0, 100.0]);
+    }
+
+    #[test]
+    fn wires_with_an_unresolved_endpoint_draw_nothing() {
+        // Layout leaves this geometry when a node or port of the wire is missing.
+        let geometry = Geometry::default();
+
+        for pattern in [
+            StrokePattern::Solid,
+            StrokePattern::Dashed(vec![10.0, 5.0]),
+            StrokePattern::Dotted(8.0),
+        ] {
+            let tessellation = stroke(&geometry.spline, &pattern, &pen(), 1.0, 12.0);
+            assert!(tessellation.vertices.is_empty());
+        }
+
+        let single = [Vector::new(4.0, 4.0)];
+        assert!(dash_polyline(&single, &[10.0, 5.0], 0.0).is_empty());
+    }
+
+    #[test]
+    fn self_loops_get_the_whole_gradient() {
+        let points = [
+            Vector::new(0.0, 0.0),
+            Vector::new(40.0, 0.0),
+            Vector::new(40.0, 40.0),
+            Vector::new(0.0, 40.0),
+            Vector::new(0.0, 0.0),
+        ];
+        let tessellation = stroke(&points, &StrokePattern::Solid, &pen(), 1.0, 0.0);
+
+        let mut buffers = Indexed {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+        append_gradient(
+            &mut buffers,
+            tessellation,
+            Color::BLACK,
+            Color::WHITE,
+            arc_length(&points),
+        );
+
+        let core = |vertex: &SolidVertex2D| vertex.color.components()[3] > 0.0;
+        let first = buffers.vertices.iter().find(|v| core(v)).unwrap();
+        let last = buffers.vertices.iter().rev().find(|v| core(v)).unwrap();
+        assert_eq!(first.color.components()[0], 0.0);
+        assert_eq!(last.color.components()[0], 1.0);
+    }
+}
@@ -0,0 +1,1116 @@
+//! Reading and writing graphs in the Graphviz DOT language.
+//!
+//! Nodes with ports are written as `record` shapes whose fields list the
+//! inputs on the left, the label in the middle and the outputs on the right.
+//! Positions are stored in the `pos` attribute with the y axis pointing up,
+//! as Graphviz expects.
+
+use iced::Point;
+
+use crate::model::{Graph, GraphEdge, GraphNode, ParseError, Subgraph};
+
+/// Writes `graph` as DOT text.
+pub fn export(graph: &Graph) -> String {
+    let (keyword, operator) = if graph.directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+
+    let mut out = String::from(keyword);
+    if let Some(name) = &graph.name {
+        out.push(' ');
+        out.push_str(&quote(name));
+    }
+    out.push_str(" {\n    rankdir=LR;\n");
+
+    for node in &graph.nodes {
+        let mut attributes = Vec::new();
+
+        if node.inputs.is_empty() && node.outputs.is_empty() {
+            if let Some(label) = &node.label {
+                attributes.push(("label".to_owned(), label.clone()));
+            }
+        } else {
+            attributes.push(("shape".to_owned(), "record".to_owned()));
+            attributes.push(("label".to_owned(), record_label(node)));
+        }
+
+        if let Some(position) = node.position {
+            attributes.push(("pos".to_owned(), format!("{},{}", position.x, -position.y)));
+        }
+
+        extend_missing(&mut attributes, &node.attributes);
+
+        out.push_str("    ");
+        out.push_str(&quote(&node.id));
+        push_attributes(&mut out, &attributes);
+        out.push_str(";\n");
+    }
+
+    for edge in &graph.edges {
+        let mut attributes = Vec::new();
+        if let Some(label) = &edge.label {
+            attributes.push(("label".to_owned(), label.clone()));
+        }
+        extend_missing(&mut attributes, &edge.attributes);
+
+        out.push_str("    ");
+        push_endpoint(&mut out, &edge.from, edge.from_port.as_deref());
+        out.push(' ');
+        out.push_str(operator);
+        out.push(' ');
+        push_endpoint(&mut out, &edge.to, edge.to_port.as_deref());
+        push_attributes(&mut out, &attributes);
+        out.push_str(";\n");
+    }
+
+    for subgraph in graph.subgraphs.iter().filter(|s| s.parent.is_none()) {
+        push_subgraph(&mut out, graph, subgraph, 1);
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Reads a graph from DOT text.
+///
+/// Named subgraphs are listed in [`Graph::subgraphs`], each node belonging to
+/// the first named subgraph it is mentioned in. Ports found in record labels
+/// or used by edges become the inputs and outputs of their node.
+pub fn parse(text: &str) -> Result<Graph, ParseError> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        graph: Graph::new(),
+        scopes: vec![Scope::default()],
+        subgraphs: Vec::new(),
+    };
+
+    parser.parse_graph()?;
+
+    let mut graph = parser.graph;
+    finish(&mut graph);
+
+    Ok(graph)
+}
+
+fn push_endpoint(out: &mut String, id: &str, port: Option<&str>) {
+    out.push_str(&quote(id));
+    if let Some(port) = port {
+        out.push(':');
+        out.push_str(&quote(port));
+    }
+}
+
+/// Appends the attributes whose key isn't set yet, so that dedicated fields win
+/// over stale copies kept in the attribute list.
+fn extend_missing(attributes: &mut Vec<(String, String)>, extra: &[(String, String)]) {
+    for (key, value) in extra {
+        if !attributes.iter().any(|(existing, _)| existing == key) {
+            attributes.push((key.clone(), value.clone()));
+        }
+    }
+}
+
+fn push_subgraph(out: &mut String, graph: &Graph, subgraph: &Subgraph, depth: usize) {
+    let indent = "    ".repeat(depth);
+
+    out.push_str(&format!("{}subgraph {} {{\n", indent, quote(&subgraph.id)));
+    if let Some(label) = &subgraph.label {
+        out.push_str(&format!("{}    label={};\n", indent, quote(label)));
+    }
+    for node in &subgraph.nodes {
+        out.push_str(&format!("{}    {};\n", indent, quote(node)));
+    }
+    for child in &graph.subgraphs {
+        if child.parent.as_deref() == Some(subgraph.id.as_str()) {
+            push_subgraph(out, graph, child, depth + 1);
+        }
+    }
+    out.push_str(&format!("{}}}\n", indent));
+}
+
+fn push_attributes(out: &mut String, attributes: &[(String, String)]) {
+    if attributes.is_empty() {
+        return;
+    }
+
+    let attributes: Vec<String> = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", quote(key), quote(value)))
+        .collect();
+
+    out.push_str(" [");
+    out.push_str(&attributes.join(", "));
+    out.push(']');
+}
+
+fn record_label(node: &GraphNode) -> String {
+    let ports = |ports: &[String]| {
+        let fields: Vec<String> = ports
+            .iter()
+            .map(|port| format!("<{}> {}", escape_record(port), escape_record(port)))
+            .collect();
+        format!("{{{}}}", fields.join("|"))
+    };
+
+    let mut fields = Vec::new();
+    if !node.inputs.is_empty() {
+        fields.push(ports(&node.inputs));
+    }
+    // The middle field is kept even when empty, as it tells inputs from outputs.
+    fields.push(escape_record(node.label.as_deref().unwrap_or_default()));
+    if !node.outputs.is_empty() {
+        fields.push(ports(&node.outputs));
+    }
+
+    format!("{{{}}}", fields.join("|"))
+}
+
+fn escape_record(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn quote(text: &str) -> String {
+    let is_identifier = text
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&text.to_ascii_lowercase().as_str());
+
+    if is_identifier {
+        text.to_owned()
+    } else {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+const KEYWORDS: [&str; 6] = ["strict", "graph", "digraph", "node", "edge", "subgraph"];
+
+const COMPASS_POINTS: [&str; 10] = ["n", "ne", "e", "se", "s", "sw", "w", "nw", "c", "_"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id { text: String, quoted: bool },
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Semicolon,
+    Comma,
+    Colon,
+    Equals,
+    Plus,
+    Arrow,
+    Line,
+    End,
+}
+
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+struct Cursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn second(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next()
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut cursor = Cursor {
+        chars: text.chars().peekable(),
+        line: 1,
+        column: 1,
+    };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = cursor.peek() {
+        let (line, column) = (cursor.line, cursor.column);
+
+        let token = match c {
+            _ if c.is_whitespace() => {
+                cursor.next();
+                continue;
+            }
+            '#' if column == 1 => {
+                while cursor.peek().is_some_and(|c| c != '\n') {
+                    cursor.next();
+                }
+                continue;
+            }
+            '/' if cursor.second() == Some('/') => {
+                while cursor.peek().is_some_and(|c| c != '\n') {
+                    cursor.next();
+                }
+                continue;
+            }
+            '/' if cursor.second() == Some('*') => {
+                cursor.next();
+                cursor.next();
+                loop {
+                    match cursor.next() {
+                        Some('*') if cursor.peek() == Some('/') => {
+                            cursor.next();
+                            break;
+                        }
+                        Some(_) => {}
+                        None => return Err(ParseError::new(line, column, "unterminated comment")),
+                    }
+                }
+                continue;
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | ':' | '=' | '+' => {
+                cursor.next();
+                match c {
+                    '{' => Token::LeftBrace,
+                    '}' => Token::RightBrace,
+                    '[' => Token::LeftBracket,
+                    ']' => Token::RightBracket,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    ':' => Token::Colon,
+                    '=' => Token::Equals,
+                    _ => Token::Plus,
+                }
+            }
+            '-' if cursor.second() == Some('>') => {
+                cursor.next();
+                cursor.next();
+                Token::Arrow
+            }
+            '-' if cursor.second() == Some('-') => {
+                cursor.next();
+                cursor.next();
+                Token::Line
+            }
+            '"' => {
+                cursor.next();
+                let mut text = String::new();
+                loop {
+                    match cursor.next() {
+                        Some('"') => break,
+                        Some('\\') => match cursor.next() {
+                            Some('"') => text.push('"'),
+                            Some('\\') => text.push('\\'),
+                            Some('\n') => {}
+                            Some(other) => {
+                                text.push('\\');
+                                text.push(other);
+                            }
+                            None => break,
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err(ParseError::new(line, column, "unterminated string")),
+                    }
+                }
+                Token::Id { text, quoted: true }
+            }
+            '<' => {
+                cursor.next();
+                let mut depth = 1;
+                let mut text = String::new();
+                loop {
+                    match cursor.next() {
+                        Some('>') if depth == 1 => break,
+                        Some(c) => {
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                _ => {}
+                            }
+                            text.push(c);
+                        }
+                        None => {
+                            return Err(ParseError::new(line, column, "unterminated HTML string"))
+                        }
+                    }
+                }
+                Token::Id { text, quoted: true }
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut text = String::new();
+                text.push(c);
+                cursor.next();
+                while let Some(c) = cursor.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        text.push(c);
+                        cursor.next();
+                    } else {
+                        break;
+                    }
+                }
+                Token::Id {
+                    text,
+                    quoted: false,
+                }
+            }
+            _ => {
+                return Err(ParseError::new(
+                    line,
+                    column,
+                    format!("unexpected character `{}`", c),
+                ))
+            }
+        };
+
+        tokens.push(Spanned {
+            token,
+            line,
+            column,
+        });
+    }
+
+    tokens.push(Spanned {
+        token: Token::End,
+        line: cursor.line,
+        column: cursor.column,
+    });
+
+    Ok(tokens)
+}
+
+#[derive(Default, Clone)]
+struct Scope {
+    node: Vec<(String, String)>,
+    edge: Vec<(String, String)>,
+}
+
+enum Operand {
+    Node { id: String, port: Option<String> },
+    Subgraph(Vec<String>),
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    index: usize,
+    graph: Graph,
+    scopes: Vec<Scope>,
+    /// Ids of the subgraphs being parsed, `None` for anonymous ones.
+    subgraphs: Vec<Option<String>>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].token
+    }
+
+    fn peek_second(&self) -> &Token {
+        let index = (self.index + 1).min(self.tokens.len() - 1);
+        &self.tokens[index].token
+    }
+
+    fn advance(&mut self) -> &Token {
+        let index = self.index;
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+        &self.tokens[index].token
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        let spanned = &self.tokens[self.index];
+        ParseError::new(spanned.line, spanned.column, message)
+    }
+
+    /// The innermost named subgraph being parsed.
+    fn subgraph(&self) -> Option<&String> {
+        self.subgraphs.iter().rev().flatten().next()
+    }
+
+    /// Sets the label of the subgraph whose statements are being parsed.
+    fn label_subgraph(&mut self, attributes: &[(String, String)]) {
+        let Some(Some(id)) = self.subgraphs.last() else {
+            return;
+        };
+
+        if let Some((_, label)) = attributes.iter().rev().find(|(key, _)| key == "label") {
+            if let Some(subgraph) = self.graph.subgraphs.iter_mut().find(|s| s.id == *id) {
+                subgraph.label = Some(label.clone());
+            }
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Id { text, quoted: false } if text.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, token: Token, description: &str) -> Result<(), ParseError> {
+        if *self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected {}", description)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, ParseError> {
+        let mut text = match self.peek() {
+            Token::Id { text, .. } => text.clone(),
+            _ => return Err(self.error("expected an identifier")),
+        };
+        self.advance();
+
+        while *self.peek() == Token::Plus {
+            self.advance();
+            match self.peek() {
+                Token::Id { text: next, .. } => text.push_str(next),
+                _ => return Err(self.error("expected a string after `+`")),
+            }
+            self.advance();
+        }
+
+        Ok(text)
+    }
+
+    fn parse_graph(&mut self) -> Result<(), ParseError> {
+        if self.is_keyword("strict") {
+            self.advance();
+        }
+
+        if self.is_keyword("digraph") {
+            self.graph.directed = true;
+        } else if self.is_keyword("graph") {
+            self.graph.directed = false;
+        } else {
+            return Err(self.error("expected `graph` or `digraph`"));
+        }
+        self.advance();
+
+        if let Token::Id { .. } = self.peek() {
+            self.graph.name = Some(self.id()?);
+        }
+
+        self.expect(Token::LeftBrace, "`{`")?;
+        self.statements()?;
+        self.expect(Token::RightBrace, "`}`")?;
+        self.expect(Token::End, "end of input")
+    }
+
+    /// Parses statements up to the closing brace, returning the nodes they mention.
+    fn statements(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut mentioned = Vec::new();
+
+        while !matches!(self.peek(), Token::RightBrace | Token::End) {
+            self.statement(&mut mentioned)?;
+
+            if *self.peek() == Token::Semicolon {
+                self.advance();
+            }
+        }
+
+        Ok(mentioned)
+    }
+
+    fn statement(&mut self, mentioned: &mut Vec<String>) -> Result<(), ParseError> {
+        if (self.is_keyword("graph") || self.is_keyword("node") || self.is_keyword("edge"))
+            && *self.peek_second() == Token::LeftBracket
+        {
+            let is_node = self.is_keyword("node");
+            let is_edge = self.is_keyword("edge");
+            self.advance();
+
+            let attributes = self.attributes()?;
+            let scope = self.scopes.last_mut().unwrap();
+            if is_node {
+                merge(&mut scope.node, attributes);
+            } else if is_edge {
+                merge(&mut scope.edge, attributes);
+            } else {
+                self.label_subgraph(&attributes);
+            }
+
+            return Ok(());
+        }
+
+        if matches!(self.peek(), Token::Id { .. }) && *self.peek_second() == Token::Equals {
+            let key = self.id()?;
+            self.advance();
+            let value = self.id()?;
+            self.label_subgraph(&[(key, value)]);
+            return Ok(());
+        }
+
+        let first = self.operand(mentioned)?;
+
+        if matches!(self.peek(), Token::Arrow | Token::Line) {
+            let mut operands = vec![first];
+
+            while matches!(self.peek(), Token::Arrow | Token::Line) {
+                let directed = *self.peek() == Token::Arrow;
+                if directed != self.graph.directed {
+                    return Err(self.error(if directed {
+                        "`->` used in an undirected graph"
+                    } else {
+                        "`--` used in a directed graph"
+                    }));
+                }
+                self.advance();
+                operands.push(self.operand(mentioned)?);
+            }
+
+            let attributes = self.optional_attributes()?;
+            self.connect(&operands, attributes);
+        } else if let Operand::Node { id, .. } = first {
+            let attributes = self.optional_attributes()?;
+            let node = self.graph.ensure_node(&id);
+            merge(&mut node.attributes, attributes);
+        }
+
+        Ok(())
+    }
+
+    fn operand(&mut self, mentioned: &mut Vec<String>) -> Result<Operand, ParseError> {
+        if self.is_keyword("subgraph") || *self.peek() == Token::LeftBrace {
+            let mut id = None;
+            if self.is_keyword("subgraph") {
+                self.advance();
+                if let Token::Id { .. } = self.peek() {
+                    id = Some(self.id()?);
+                }
+            }
+
+            if let Some(id) = &id {
+                if !self.graph.subgraphs.iter().any(|s| s.id == *id) {
+                    let parent = self.subgraph().cloned();
+                    self.graph.subgraphs.push(Subgraph {
+                        id: id.clone(),
+                        label: None,
+                        parent,
+                        nodes: Vec::new(),
+                    });
+                }
+            }
+
+            self.expect(Token::LeftBrace, "`{`")?;
+            let scope = self.scopes.last().cloned().unwrap_or_default();
+            self.scopes.push(scope);
+            self.subgraphs.push(id);
+            let nodes = self.statements()?;
+            self.subgraphs.pop();
+            self.scopes.pop();
+            self.expect(Token::RightBrace, "`}`")?;
+
+            mentioned.extend(nodes.iter().cloned());
+            return Ok(Operand::Subgraph(nodes));
+        }
+
+        let id = self.id()?;
+        let mut port = None;
+
+        // A lone compass point may also name a port, which is told apart once the
+        // record labels are known.
+        if *self.peek() == Token::Colon {
+            self.advance();
+            port = Some(self.id()?);
+
+            if *self.peek() == Token::Colon {
+                self.advance();
+                self.id()?;
+            }
+        }
+
+        if self.graph.node(&id).is_none() {
+            let defaults = self.scopes.last().unwrap().node.clone();
+            self.graph.ensure_node(&id).attributes = defaults;
+        }
+
+        if let Some(subgraph) = self.subgraph().cloned() {
+            let assigned = self.graph.subgraphs.iter().any(|s| s.nodes.contains(&id));
+            if !assigned {
+                if let Some(subgraph) = self.graph.subgraphs.iter_mut().find(|s| s.id == subgraph) {
+                    subgraph.nodes.push(id.clone());
+                }
+            }
+        }
+
+        mentioned.push(id.clone());
+        Ok(Operand::Node { id, port })
+    }
+
+    fn connect(&mut self, operands: &[Operand], attributes: Vec<(String, String)>) {
+        let mut edge_attributes = self.scopes.last().unwrap().edge.clone();
+        merge(&mut edge_attributes, attributes);
+
+        let endpoints = |operand: &Operand| match operand {
+            Operand::Node { id, port } => vec![(id.clone(), port.clone())],
+            Operand::Subgraph(nodes) => nodes.iter().map(|id| (id.clone(), None)).collect(),
+        };
+
+        for pair in operands.windows(2) {
+            for (from, from_port) in endpoints(&pair[0]) {
+                for (to, to_port) in endpoints(&pair[1]) {
+                    let mut edge = GraphEdge::new(from.clone(), to);
+                    edge.from_port = from_port.clone();
+                    edge.to_port = to_port;
+                    edge.attributes = edge_attributes.clone();
+                    self.graph.edges.push(edge);
+                }
+            }
+        }
+    }
+
+    fn optional_attributes(&mut self) -> Result<Vec<(String, String)>, ParseError> {
+        if *self.peek() == Token::LeftBracket {
+            self.attributes()
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, ParseError> {
+        let mut attributes = Vec::new();
+
+        while *self.peek() == Token::LeftBracket {
+            self.advance();
+
+            while *self.peek() != Token::RightBracket {
+                let key = self.id()?;
+                self.expect(Token::Equals, "`=`")?;
+                let value = self.id()?;
+                merge(&mut attributes, vec![(key, value)]);
+
+                if matches!(self.peek(), Token::Comma | Token::Semicolon) {
+                    self.advance();
+                }
+            }
+
+            self.advance();
+        }
+
+        Ok(attributes)
+    }
+}
+
+fn merge(attributes: &mut Vec<(String, String)>, new: Vec<(String, String)>) {
+    for (key, value) in new {
+        match attributes.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(attribute) => attribute.1 = value,
+            None => attributes.push((key, value)),
+        }
+    }
+}
+
+fn take(attributes: &mut Vec<(String, String)>, key: &str) -> Option<String> {
+    let index = attributes
+        .iter()
+        .position(|(existing, _)| existing == key)?;
+    Some(attributes.remove(index).1)
+}
+
+/// Moves the attributes with a dedicated field out of the attribute lists and
+/// assigns ports to the side they are used on.
+fn finish(graph: &mut Graph) {
+    let mut unassigned = Vec::new();
+
+    for node in &mut graph.nodes {
+        let label = take(&mut node.attributes, "label").filter(|label| label != "\\N");
+
+        let shape = take(&mut node.attributes, "shape");
+        match shape.as_deref() {
+            Some("record") | Some("Mrecord") => {
+                let record = parse_record(label.as_deref().unwrap_or(&node.id));
+                node.label = record.title;
+                node.inputs = record.inputs;
+                node.outputs = record.outputs;
+                unassigned.push((node.id.clone(), record.unassigned));
+            }
+            _ => {
+                node.label = label;
+                if let Some(shape) = shape {
+                    node.attributes.push(("shape".to_owned(), shape));
+                }
+            }
+        }
+
+        if let Some(pos) = take(&mut node.attributes, "pos") {
+            node.position = parse_position(&pos);
+        }
+    }
+
+    for edge in &mut graph.edges {
+        edge.label = take(&mut edge.attributes, "label");
+
+        if let Some(port) = take(&mut edge.attributes, "tailport") {
+            edge.from_port.get_or_insert(port);
+        }
+        if let Some(port) = take(&mut edge.attributes, "headport") {
+            edge.to_port.get_or_insert(port);
+        }
+    }
+
+    // Compass points are only ports when the record of the node declares them.
+    let declares = |id: &str, port: &str| {
+        graph
+            .node(id)
+            .is_some_and(|node| node.inputs.iter().chain(&node.outputs).any(|p| p == port))
+            || unassigned
+                .iter()
+                .any(|(node, ports)| node == id && ports.iter().any(|p| p == port))
+    };
+    let compass: Vec<(usize, bool)> = graph
+        .edges
+        .iter()
+        .enumerate()
+        .flat_map(|(i, edge)| {
+            [
+                (i, false, &edge.from, &edge.from_port),
+                (i, true, &edge.to, &edge.to_port),
+            ]
+        })
+        .filter(|(_, _, id, port)| {
+            port.as_deref()
+                .is_some_and(|port| COMPASS_POINTS.contains(&port) && !declares(id.as_str(), port))
+        })
+        .map(|(i, head, _, _)| (i, head))
+        .collect();
+    for (i, head) in compass {
+        if head {
+            graph.edges[i].to_port = None;
+        } else {
+            graph.edges[i].from_port = None;
+        }
+    }
+
+    let ports: Vec<_> = graph
+        .edges
+        .iter()
+        .flat_map(|edge| {
+            [
+                edge.from_port
+                    .clone()
+                    .map(|port| (edge.from.clone(), port, false)),
+                edge.to_port
+                    .clone()
+                    .map(|port| (edge.to.clone(), port, true)),
+            ]
+        })
+        .flatten()
+        .collect();
+
+    for (id, port, is_input) in ports {
+        let node = graph.ensure_node(&id);
+        if node.inputs.contains(&port) || node.outputs.contains(&port) {
+            continue;
+        }
+
+        if is_input {
+            node.inputs.push(port);
+        } else {
+            node.outputs.push(port);
+        }
+    }
+
+    for (id, ports) in unassigned {
+        let node = graph.ensure_node(&id);
+        for port in ports {
+            if !node.inputs.contains(&port) && !node.outputs.contains(&port) {
+                node.inputs.push(port);
+            }
+        }
+    }
+}
+
+fn parse_position(text: &str) -> Option<Point> {
+    let mut coordinates = text.trim_end_matches('!').split(',');
+    let x = coordinates.next()?.trim().parse::<f32>().ok()?;
+    let y = coordinates.next()?.trim().parse::<f32>().ok()?;
+
+    Some(Point::new(x, -y))
+}
+
+enum Field {
+    Text { port: Option<String>, text: String },
+    Group(Vec<Field>),
+}
+
+struct Record {
+    title: Option<String>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    unassigned: Vec<String>,
+}
+
+/// Splits a record label into the ports before and after its first plain field.
+fn parse_record(label: &str) -> Record {
+    let chars: Vec<char> = label.chars().collect();
+    let mut index = 0;
+    let mut fields = record_fields(&chars, &mut index);
+
+    while fields.len() == 1 && matches!(fields[0], Field::Group(_)) {
+        match fields.pop() {
+            Some(Field::Group(inner)) => fields = inner,
+            _ => unreachable!(),
+        }
+    }
+
+    let title = fields
+        .iter()
+        .position(|field| matches!(field, Field::Text { port: None, .. }));
+
+    let mut record = Record {
+        title: None,
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        unassigned: Vec::new(),
+    };
+
+    match title {
+        Some(title) => {
+            if let Field::Text { text, .. } = &fields[title] {
+                record.title = Some(text.clone()).filter(|text| !text.is_empty());
+            }
+            for field in &fields[..title] {
+                collect_ports(field, &mut record.inputs);
+            }
+            for field in &fields[title + 1..] {
+                collect_ports(field, &mut record.outputs);
+            }
+        }
+        None => {
+            for field in &fields {
+                collect_ports(field, &mut record.unassigned);
+            }
+        }
+    }
+
+    record
+}
+
+fn record_fields(chars: &[char], index: &mut usize) -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    loop {
+        while chars.get(*index).is_some_and(|c| c.is_whitespace()) {
+            *index += 1;
+        }
+
+        if chars.get(*index) == Some(&'{') {
+            *index += 1;
+            fields.push(Field::Group(record_fields(chars, index)));
+            if chars.get(*index) == Some(&'}') {
+                *index += 1;
+            }
+            while chars.get(*index).is_some_and(|c| c.is_whitespace()) {
+                *index += 1;
+            }
+        } else {
+            let mut port = None;
+            let mut text = String::new();
+
+            while let Some(&c) = chars.get(*index) {
+                match c {
+                    '|' | '}' => break,
+                    '\\' => {
+                        *index += 1;
+                        if let Some(&escaped) = chars.get(*index) {
+                            text.push(escaped);
+                        }
+                    }
+                    '<' => {
+                        let mut name = String::new();
+                        *index += 1;
+                        while let Some(&c) = chars.get(*index) {
+                            match c {
+                                '>' => break,
+                                '\\' => {
+                                    *index += 1;
+                                    if let Some(&escaped) = chars.get(*index) {
+                                        name.push(escaped);
+                                    }
+                                }
+                                _ => name.push(c),
+                            }
+                            *index += 1;
+                        }
+                        port = Some(name.trim().to_owned());
+                    }
+                    _ => text.push(c),
+                }
+                *index += 1;
+            }
+
+            fields.push(Field::Text {
+                port,
+                text: text.trim().to_owned(),
+            });
+        }
+
+        match chars.get(*index) {
+            Some('|') => *index += 1,
+            _ => break,
+        }
+    }
+
+    fields
+}
+
+fn collect_ports(field: &Field, ports: &mut Vec<String>) {
+    match field {
+        Field::Text {
+            port: Some(port), ..
+        } => ports.push(port.clone()),
+        Field::Text { .. } => {}
+        Field::Group(fields) => {
+            for field in fields {
+                collect_ports(field, ports);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Graph {
+        let mut graph = Graph::new();
+        graph.name = Some("pipe line".to_owned());
+
+        let mut source = GraphNode::new("source");
+        source.label = Some("Read \"input\" from C:\\data\\".to_owned());
+        source.outputs = vec!["e".to_owned(), "value".to_owned()];
+        source.position = Some(Point::new(10.0, 20.0));
+        source.attributes = vec![("color".to_owned(), "red".to_owned())];
+        graph.nodes.push(source);
+
+        let mut sink = GraphNode::new("sink");
+        sink.inputs = vec!["in".to_owned()];
+        graph.nodes.push(sink);
+
+        let mut blank = GraphNode::new("blank");
+        blank.outputs = vec!["out".to_owned()];
+        graph.nodes.push(blank);
+
+        graph.nodes.push(GraphNode::new("plain"));
+
+        let mut edge = GraphEdge::new("source", "sink");
+        edge.from_port = Some("e".to_owned());
+        edge.to_port = Some("in".to_owned());
+        edge.label = Some("a|b".to_owned());
+        graph.edges.push(edge);
+        graph.edges.push(GraphEdge::new("blank", "plain"));
+
+        graph.subgraphs.push(Subgraph {
+            id: "cluster_outer".to_owned(),
+            label: Some("Outer".to_owned()),
+            parent: None,
+            nodes: vec!["source".to_owned()],
+        });
+        graph.subgraphs.push(Subgraph {
+            id: "cluster_inner".to_owned(),
+            label: None,
+            parent: Some("cluster_outer".to_owned()),
+            nodes: vec!["sink".to_owned(), "blank".to_owned()],
+        });
+
+        graph
+    }
+
+    #[test]
+    fn export_then_parse_round_trips() {
+        let graph = sample();
+        let text = export(&graph);
+
+        assert_eq!(parse(&text), Ok(graph.clone()), "{text}");
+        assert_eq!(parse(&export(&parse(&text).unwrap())), Ok(graph));
+    }
+
+    #[test]
+    fn dedicated_fields_win_over_attributes() {
+        let mut node = GraphNode::new("a");
+        node.inputs = vec!["x".to_owned()];
+        node.label = Some("A".to_owned());
+        node.attributes = vec![
+            ("shape".to_owned(), "box".to_owned()),
+            ("label".to_owned(), "stale".to_owned()),
+        ];
+        let mut graph = Graph::new();
+        graph.nodes.push(node);
+
+        let text = export(&graph);
+        assert_eq!(text.matches("shape=").count(), 1, "{text}");
+        assert_eq!(text.matches("label=").count(), 1, "{text}");
+        assert!(!text.contains("stale"));
+    }
+
+    #[test]
+    fn compass_points_are_dropped_unless_declared() {
+        let graph = parse(
+            "digraph { a [shape=record, label=\"{A|{<e> e}}\"]; a:e -> b:n; a:e:s -> b:w:c }",
+        )
+        .unwrap();
+
+        assert_eq!(graph.node("a").unwrap().outputs, vec!["e".to_owned()]);
+        assert!(graph.node("b").unwrap().inputs.is_empty());
+        assert_eq!(graph.edges[0].from_port.as_deref(), Some("e"));
+        assert_eq!(graph.edges[0].to_port, None);
+        assert_eq!(graph.edges[1].to_port, None);
+    }
+
+    #[test]
+    fn parses_foreign_records_and_escapes() {
+        let graph = parse(
+            "digraph G {\n  node [shape=Mrecord];\n  n [label=\"{<i> in|Sum \\| total|<o> out}\"];\n  m [shape=box, label=\"a\\\\b\"];\n}",
+        )
+        .unwrap();
+
+        let n = graph.node("n").unwrap();
+        assert_eq!(n.label.as_deref(), Some("Sum | total"));
+        assert_eq!(n.inputs, vec!["i".to_owned()]);
+        assert_eq!(n.outputs, vec!["o".to_owned()]);
+        assert_eq!(graph.node("m").unwrap().label.as_deref(), Some("a\\b"));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        let error = parse("digraph {\n  a -> ;\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 8));
+
+        let error = parse("graph {\n  a -> b\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+
+        let error = parse("digraph {\n  a [label=\"open\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 12));
+
+        let error = parse("digraph { a }\n  b").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse("digraph {\n  a @ b\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use iced::{
     advanced::{
         layout,
@@ -5,16 +7,28 @@ use iced::{
         widget::{self, Operation},
         Clipboard, Layout, Shell, Widget,
     },
-    event, mouse, Background, Color, Element, Event, Length, Point, Rectangle, Size, Vector,
+    event, keyboard, mouse,
+    time::Instant,
+    window::RedrawRequest,
+    Background, Color, Element, Event, Length, Point, Rectangle, Size, Vector,
 };
 
 use crate::{
     matrix::Matrix,
+    node_element::LayoutContext,
+    port::{Endpoint, PortAnchors},
     styles::graph_container::{Appearance, StyleSheet},
-    GraphNodeElement,
+    tooltip::TooltipStyle,
+    ConnectionRouting, GraphEvent, GraphNodeElement, Mode, RouteCache,
 };
 
-pub struct GraphContainer<'a, Message, Renderer>
+const ZOOM_FACTOR: f32 = 1.2;
+const DRAG_THRESHOLD: f32 = 4.0;
+const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+type EventHandler<'a, Id, Message> = Box<dyn Fn(GraphEvent<Id>) -> Message + 'a>;
+
+pub struct GraphContainer<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -24,26 +38,70 @@ where
     max_width: f32,
     max_height: f32,
     style: <Renderer::Theme as StyleSheet>::Style,
-    content: Vec<GraphNodeElement<'a, Message, Renderer>>,
+    content: Vec<GraphNodeElement<'a, Id, Message, Renderer>>,
     matrix: Matrix,
-    on_translate: Option<Box<dyn Fn((f32, f32)) -> Message + 'a>>,
-    on_scale: Option<Box<dyn Fn(f32, f32, f32) -> Message + 'a>>,
+    routing: ConnectionRouting,
+    route_cache: Option<&'a RouteCache>,
+    raise_selected: bool,
+    drag_threshold: f32,
+    tooltip_delay: Duration,
+    on_event: Option<EventHandler<'a, Id, Message>>,
+}
+
+struct GraphContainerState<Id> {
+    drag: Option<Drag<Id>>,
+    modifiers: keyboard::Modifiers,
+    /// Whether the last press was inside the container, which then gets key presses.
+    focused: bool,
+    measured: Vec<(Id, Size)>,
+    /// Nodes linked by every connection, once the container has seen them.
+    wires: Option<Vec<Wire<Id>>>,
+    tooltip: Option<Hover>,
+}
+
+/// A tooltip under the cursor, shown once the cursor has rested on it for the delay.
+struct Hover {
+    /// Index of the element and key of its tooltip.
+    target: (usize, usize),
+    since: Instant,
 }
 
-struct GraphContainerState {
-    drag_start_position: Option<Point>,
+enum Drag<Id> {
+    Camera {
+        origin: Point,
+        matrix: Matrix,
+    },
+    /// A node was pressed, and becomes a click or a drag depending on how far the
+    /// cursor moves before it is released.
+    Pressed {
+        origin: Point,
+        id: Id,
+        ids: Vec<Id>,
+        draggable: bool,
+        /// Whether a click selects only the pressed node, which was part of a larger
+        /// selection kept in case it is dragged.
+        narrows_selection: bool,
+    },
+    Nodes {
+        last_position: Point,
+        ids: Vec<Id>,
+    },
 }
 
-impl<'a, Message, Renderer> GraphContainer<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> GraphContainer<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
-    pub fn new(content: Vec<GraphNodeElement<'a, Message, Renderer>>) -> Self {
+    pub fn new(content: Vec<GraphNodeElement<'a, Id, Message, Renderer>>) -> Self {
         GraphContainer {
-            on_translate: None,
-            on_scale: None,
+            on_event: None,
             matrix: Matrix::identity(),
+            routing: ConnectionRouting::default(),
+            route_cache: None,
+            raise_selected: false,
+            drag_threshold: DRAG_THRESHOLD,
+            tooltip_delay: TOOLTIP_DELAY,
             width: Length::Shrink,
             height: Length::Shrink,
             max_width: f32::MAX,
@@ -53,24 +111,48 @@ where
         }
     }
 
-    pub fn on_translate<F>(mut self, f: F) -> Self
+    pub fn on_event<F>(mut self, f: F) -> Self
     where
-        F: 'a + Fn((f32, f32)) -> Message,
+        F: 'a + Fn(GraphEvent<Id>) -> Message,
     {
-        self.on_translate = Some(Box::new(f));
+        self.on_event = Some(Box::new(f));
         self
     }
 
-    pub fn on_scale<F>(mut self, f: F) -> Self
-    where
-        F: 'a + Fn(f32, f32, f32) -> Message,
-    {
-        self.on_scale = Some(Box::new(f));
+    pub fn matrix(mut self, m: Matrix) -> Self {
+        self.matrix = m;
         self
     }
 
-    pub fn matrix(mut self, m: Matrix) -> Self {
-        self.matrix = m;
+    /// Routing of the connections that don't set their own.
+    pub fn routing(mut self, routing: ConnectionRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Reuses the paths of connections routed around nodes across layouts.
+    pub fn route_cache(mut self, cache: &'a RouteCache) -> Self {
+        self.route_cache = Some(cache);
+        self
+    }
+
+    /// Draws selected elements above the others, whatever their z-index.
+    pub fn raise_selected(mut self, raise_selected: bool) -> Self {
+        self.raise_selected = raise_selected;
+        self
+    }
+
+    /// Distance in pixels the cursor has to move while pressing a node before it is
+    /// dragged, so that clicks don't nudge nodes.
+    pub fn drag_threshold(mut self, drag_threshold: f32) -> Self {
+        self.drag_threshold = drag_threshold;
+        self
+    }
+
+    /// How long the cursor has to rest on a node, a port or a connection before its
+    /// tooltip is shown.
+    pub fn tooltip_delay(mut self, tooltip_delay: Duration) -> Self {
+        self.tooltip_delay = tooltip_delay;
         self
     }
 
@@ -100,9 +182,9 @@ where
     }
 }
 
-pub fn graph_container<'a, Message, Renderer>(
-    content: Vec<GraphNodeElement<'a, Message, Renderer>>,
-) -> GraphContainer<'a, Message, Renderer>
+pub fn graph_container<'a, Id, Message, Renderer>(
+    content: Vec<GraphNodeElement<'a, Id, Message, Renderer>>,
+) -> GraphContainer<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -110,8 +192,125 @@ where
     GraphContainer::new(content)
 }
 
-impl<'a, Message, Renderer> Widget<Message, Renderer> for GraphContainer<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> GraphContainer<'a, Id, Message, Renderer>
 where
+    Id: Clone + PartialEq,
+    Renderer: renderer::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    fn selection(&self) -> Vec<Id> {
+        self.content
+            .iter()
+            .map(|child| child.as_scalable_widget())
+            .filter(|child| child.is_selected())
+            .filter_map(|child| child.id().cloned())
+            .collect()
+    }
+
+    /// Indices of the elements from the bottom to the top.
+    fn order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.content.len()).collect();
+
+        order.sort_by_key(|i| {
+            let child = self.content[*i].as_scalable_widget();
+            (self.raise_selected && child.is_selected(), child.z_index())
+        });
+
+        order
+    }
+
+    /// Index of the topmost element with an id at `position`.
+    fn node_at(&self, layout: Layout<'_>, position: Point) -> Option<usize> {
+        let layouts: Vec<_> = layout.children().collect();
+
+        self.order().into_iter().rev().find(|i| {
+            layouts[*i].bounds().contains(position)
+                && self.content[*i].as_scalable_widget().id().is_some()
+        })
+    }
+
+    /// Index of the element with a tooltip at `position` and the key of the tooltip,
+    /// looking no further down than the topmost node at `position`.
+    fn tooltip_at(&self, layout: Layout<'_>, position: Point) -> Option<(usize, usize)> {
+        let layouts: Vec<_> = layout.children().collect();
+
+        for i in self.order().into_iter().rev() {
+            let child = self.content[i].as_scalable_widget();
+
+            if let Some(tooltip) = child.tooltip_at(layouts[i], position) {
+                return Some((i, tooltip));
+            }
+            if child.is_obstacle() && layouts[i].bounds().contains(position) {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    /// The nodes linked by every connection between two ports.
+    fn wires(&self) -> Vec<Wire<Id>> {
+        self.content
+            .iter()
+            .filter_map(|child| match child.as_scalable_widget().endpoints()[..] {
+                [Endpoint::Output(from, _), Endpoint::Input(to, _)]
+                | [Endpoint::Input(to, _), Endpoint::Output(from, _)] => {
+                    Some((from.clone(), to.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The modes of the selected nodes once `mode` is toggled on them: `mode`, or
+    /// [`Mode::Active`] when they all have it already.
+    fn toggled_modes(&self, mode: Mode) -> Vec<(Id, Mode)> {
+        let selected: Vec<(Id, Mode)> = self
+            .content
+            .iter()
+            .map(|child| child.as_scalable_widget())
+            .filter(|child| child.is_selected())
+            .filter_map(|child| Some((child.id()?.clone(), child.mode())))
+            .collect();
+
+        let mode = if selected.iter().all(|(_, current)| *current == mode) {
+            Mode::Active
+        } else {
+            mode
+        };
+
+        selected.into_iter().map(|(id, _)| (id, mode)).collect()
+    }
+
+    fn measure(&self, layout: Layout<'_>) -> Vec<(Id, Size)> {
+        let scale = self.matrix.get_scale();
+
+        self.content
+            .iter()
+            .zip(layout.children())
+            .filter_map(|(child, layout)| {
+                let id = child.as_scalable_widget().id()?;
+                let size = layout.bounds().size();
+
+                Some((
+                    id.clone(),
+                    Size::new(size.width / scale, size.height / scale),
+                ))
+            })
+            .collect()
+    }
+
+    fn publish(&self, shell: &mut Shell<'_, Message>, event: GraphEvent<Id>) {
+        if let Some(f) = &self.on_event {
+            shell.publish(f(event));
+        }
+    }
+}
+
+impl<'a, Id, Message, Renderer> Widget<Message, Renderer>
+    for GraphContainer<'a, Id, Message, Renderer>
+where
+    Id: Clone + PartialEq + 'static,
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
@@ -138,12 +337,17 @@ where
     }
 
     fn tag(&self) -> widget::tree::Tag {
-        widget::tree::Tag::of::<widget::tree::State>()
+        widget::tree::Tag::of::<GraphContainerState<Id>>()
     }
-    
+
     fn state(&self) -> widget::tree::State {
-        widget::tree::State::new(GraphContainerState {
-            drag_start_position: None,
+        widget::tree::State::new(GraphContainerState::<Id> {
+            drag: None,
+            modifiers: keyboard::Modifiers::default(),
+            focused: false,
+            measured: Vec::new(),
+            wires: None,
+            tooltip: None,
         })
     }
 
@@ -155,16 +359,71 @@ where
             .width(self.width)
             .height(self.height);
 
-        let mut content = Vec::new();
+        let mut context = LayoutContext {
+            scale: self.matrix.get_scale(),
+            routing: self.routing,
+            obstacles: &[],
+            ports: &[],
+            connected: &[],
+            route_cache: self.route_cache,
+        };
+        let offset = Vector::new(
+            self.matrix.get_translation().0,
+            self.matrix.get_translation().1,
+        );
 
-        let scale = self.matrix.get_scale();
-        let offset = self.matrix.get_translation();
+        let connected: Vec<Endpoint<Id>> = self
+            .content
+            .iter()
+            .flat_map(|node| node.as_scalable_widget().endpoints())
+            .filter(|endpoint| !matches!(endpoint, Endpoint::Point(_)))
+            .cloned()
+            .collect();
+        context.connected = &connected;
 
-        for node in &self.content {
-            let mut node = node.as_scalable_widget().layout(_renderer, &limits, scale);
-            node = node.translate(Vector::new(offset.0, offset.1));
+        // Obstacles go first, so that the other elements can be routed around them.
+        let mut content: Vec<Option<layout::Node>> = self
+            .content
+            .iter()
+            .map(|node| {
+                let node = node.as_scalable_widget();
+                node.is_obstacle()
+                    .then(|| node.layout(_renderer, &limits, &context))
+            })
+            .collect();
 
-            content.push(node);
+        let obstacles: Vec<Rectangle> =
+            content.iter().flatten().map(|node| node.bounds()).collect();
+        let ports: Vec<(Id, PortAnchors)> = self
+            .content
+            .iter()
+            .zip(&content)
+            .filter_map(|(node, laid_out)| {
+                let node = node.as_scalable_widget();
+                let anchors = node.port_anchors(Layout::new(laid_out.as_ref()?));
+                Some((node.id()?.clone(), anchors))
+            })
+            .collect();
+        context.obstacles = &obstacles;
+        context.ports = &ports;
+
+        let content = self
+            .content
+            .iter()
+            .zip(content.iter_mut())
+            .map(|(node, laid_out)| {
+                laid_out
+                    .take()
+                    .unwrap_or_else(|| {
+                        node.as_scalable_widget()
+                            .layout(_renderer, &limits, &context)
+                    })
+                    .translate(offset)
+            })
+            .collect();
+
+        if let Some(cache) = self.route_cache {
+            cache.trim();
         }
 
         let size = limits.resolve(Size::ZERO);
@@ -204,66 +463,254 @@ where
         viewport: &Rectangle<f32>,
     ) -> event::Status {
         let mut status = event::Status::Ignored;
-        let mut state = tree.state.downcast_mut::<GraphContainerState>();
+        let state = tree.state.downcast_mut::<GraphContainerState<Id>>();
 
-        if let Some(start) = state.drag_start_position {
-            if let Some(cursor_position) = cursor.position() {
-                match event {
-                    Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                        state.drag_start_position = None;
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            state.modifiers = modifiers;
+        }
+
+        let measured = self.measure(layout);
+        if !same_measurements(&state.measured, &measured) {
+            state.measured = measured.clone();
+            self.publish(shell, GraphEvent::NodesMeasured(measured));
+        }
+
+        let wires = self.wires();
+        if let Some(previous) = state.wires.replace(wires.clone()) {
+            let (added, removed) = changed_wires(&previous, &wires);
+
+            for (from, to) in removed {
+                self.publish(shell, GraphEvent::Disconnected { from, to });
+            }
+            for (from, to) in added {
+                self.publish(shell, GraphEvent::Connected { from, to });
+            }
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let target = cursor
+                    .position_over(layout.bounds())
+                    .filter(|_| state.drag.is_none())
+                    .and_then(|position| self.tooltip_at(layout, position));
+
+                if target != state.tooltip.as_ref().map(|hover| hover.target) {
+                    state.tooltip = target.map(|target| {
+                        let since = Instant::now();
+                        shell.request_redraw(RedrawRequest::At(since + self.tooltip_delay));
+                        Hover { target, since }
+                    });
+                }
+            }
+            Event::Mouse(mouse::Event::CursorLeft | mouse::Event::ButtonPressed(_)) => {
+                state.tooltip = None;
+            }
+            _ => {}
+        }
+
+        // Frame events still reach the children while dragging, to keep animations running.
+        let dispatch = state.drag.is_none() || matches!(event, Event::Window(_));
+
+        if let Event::Mouse(mouse::Event::ButtonPressed(_)) = event {
+            state.focused = cursor.is_over(layout.bounds());
+        }
+
+        // A release ends the drag even when the cursor has left the window.
+        if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event {
+            match state.drag.take() {
+                Some(Drag::Nodes { ids, .. }) => {
+                    for id in ids {
+                        self.publish(shell, GraphEvent::NodeDragEnded(id));
+                    }
+                }
+                Some(Drag::Pressed {
+                    origin,
+                    id,
+                    narrows_selection,
+                    ..
+                }) if cursor
+                    .position()
+                    .is_some_and(|position| position.distance(origin) <= self.drag_threshold) =>
+                {
+                    if narrows_selection {
+                        self.publish(shell, GraphEvent::SelectionChanged(vec![id.clone()]));
+                    }
+                    self.publish(shell, GraphEvent::NodeClicked(id));
+                }
+                _ => {}
+            }
+        } else if let (Some(drag), Some(cursor_position)) = (&mut state.drag, cursor.position()) {
+            if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
+                match drag {
+                    Drag::Camera { origin, matrix } => {
+                        let delta = cursor_position - *origin;
+                        let matrix = matrix.translate(delta.x, delta.y);
+                        self.publish(shell, GraphEvent::CameraChanged(matrix));
+                    }
+                    Drag::Pressed {
+                        origin,
+                        ids,
+                        draggable,
+                        ..
+                    } => {
+                        if *draggable && cursor_position.distance(*origin) > self.drag_threshold {
+                            let delta =
+                                (cursor_position - *origin) * (1.0 / self.matrix.get_scale());
+                            let ids = std::mem::take(ids);
+
+                            for id in &ids {
+                                self.publish(shell, GraphEvent::NodeDragStarted(id.clone()));
+                                self.publish(
+                                    shell,
+                                    GraphEvent::NodeMoved {
+                                        id: id.clone(),
+                                        delta,
+                                    },
+                                );
+                            }
+
+                            *drag = Drag::Nodes {
+                                last_position: cursor_position,
+                                ids,
+                            };
+                        }
                     }
-                    Event::Mouse(mouse::Event::CursorMoved { .. }) => {
-                        let delta = cursor_position - start;
-                        state.drag_start_position = Some(cursor_position);
-                        if let Some(f) = &self.on_translate {
-                            let message = f((delta.x, delta.y));
-                            shell.publish(message);
+                    Drag::Nodes { last_position, ids } => {
+                        let delta =
+                            (cursor_position - *last_position) * (1.0 / self.matrix.get_scale());
+                        *last_position = cursor_position;
+
+                        for id in ids.iter() {
+                            self.publish(
+                                shell,
+                                GraphEvent::NodeMoved {
+                                    id: id.clone(),
+                                    delta,
+                                },
+                            );
                         }
-                        status = event::Status::Captured;
                     }
-                    _ => {}
                 }
+                status = event::Status::Captured;
             }
-        } else {
-            status = self
+        }
+
+        if dispatch {
+            // Children are offered events top-down, and the ones below a child that
+            // captured an event, or below the node under the cursor, no longer see it.
+            let order = self.order();
+            let mut children: Vec<_> = self
                 .content
                 .iter_mut()
                 .zip(&mut tree.children)
                 .zip(layout.children())
-                .map(|((child, state), layout)| {
-                    child.as_widget_mut().on_event(
-                        state,
-                        event.clone(),
-                        layout,
-                        cursor,
-                        renderer,
-                        clipboard,
-                        shell,
-                        viewport,
-                    )
-                })
-                .fold(event::Status::Ignored, event::Status::merge);
+                .collect();
+            let mut cursor = cursor;
+
+            for i in order.into_iter().rev() {
+                let ((child, state), layout) = &mut children[i];
+                let child_status = child.as_widget_mut().on_event(
+                    state,
+                    event.clone(),
+                    *layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    shell,
+                    viewport,
+                );
+
+                if child_status == event::Status::Captured
+                    || child.as_scalable_widget().is_obstacle() && cursor.is_over(layout.bounds())
+                {
+                    cursor = mouse::Cursor::Unavailable;
+                }
+                status = status.merge(child_status);
+            }
         }
 
         if status == event::Status::Ignored {
-            if let Some(cursor_position) = cursor.position() {
+            if let Some(cursor_position) = cursor.position_over(layout.bounds()) {
                 match event {
                     Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
-                        state.drag_start_position = Some(cursor_position);
+                        let additive = state.modifiers.shift() || state.modifiers.command();
+                        let selection = self.selection();
+
+                        if let Some(index) = self.node_at(layout, cursor_position) {
+                            let node = self.content[index].as_scalable_widget();
+                            let id = node.id().cloned().expect("Node without id");
+                            let draggable = node.is_draggable_at(
+                                layout.children().nth(index).expect("Missing node layout"),
+                                cursor_position,
+                            );
+                            let selected = selection.contains(&id);
+
+                            let new_selection = if additive && selected {
+                                selection.iter().filter(|s| **s != id).cloned().collect()
+                            } else if additive {
+                                selection.iter().cloned().chain([id.clone()]).collect()
+                            } else if selected {
+                                selection.clone()
+                            } else {
+                                vec![id.clone()]
+                            };
+
+                            if new_selection != selection {
+                                self.publish(
+                                    shell,
+                                    GraphEvent::SelectionChanged(new_selection.clone()),
+                                );
+                            }
+
+                            let narrows_selection = !additive && selected && selection.len() > 1;
+                            let ids = if new_selection.contains(&id) {
+                                new_selection
+                            } else {
+                                vec![id.clone()]
+                            };
+
+                            state.drag = Some(Drag::Pressed {
+                                origin: cursor_position,
+                                id,
+                                ids,
+                                draggable,
+                                narrows_selection,
+                            });
+                        } else {
+                            if !additive && !selection.is_empty() {
+                                self.publish(shell, GraphEvent::SelectionChanged(Vec::new()));
+                            }
+
+                            state.drag = Some(Drag::Camera {
+                                origin: cursor_position,
+                                matrix: self.matrix,
+                            });
+                        }
+
                         status = event::Status::Captured;
                     }
                     Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
-                        if let Some(f) = &self.on_scale {
-                            match delta {
-                                mouse::ScrollDelta::Lines { y, .. } => {
-                                    let message = f(cursor_position.x, cursor_position.y, y);
-                                    shell.publish(message);
-                                }
-                                mouse::ScrollDelta::Pixels { y, .. } => {
-                                    let message = f(cursor_position.x, cursor_position.y, y);
-                                    shell.publish(message);
-                                }
-                            }
+                        let y = match delta {
+                            mouse::ScrollDelta::Lines { y, .. } => y,
+                            mouse::ScrollDelta::Pixels { y, .. } => y,
+                        };
+
+                        if y != 0.0 {
+                            let bounds = layout.bounds();
+                            let x = cursor_position.x - bounds.x;
+                            let y_position = cursor_position.y - bounds.y;
+
+                            let matrix = self
+                                .matrix
+                                .translate(-x, -y_position)
+                                .scale(if y > 0.0 {
+                                    ZOOM_FACTOR
+                                } else {
+                                    1.0 / ZOOM_FACTOR
+                                })
+                                .translate(x, y_position);
+
+                            self.publish(shell, GraphEvent::CameraChanged(matrix));
                             status = event::Status::Captured;
                         }
                     }
@@ -272,6 +719,40 @@ where
             }
         }
 
+        if let Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: keyboard::KeyCode::Delete | keyboard::KeyCode::Backspace,
+            ..
+        }) = event
+        {
+            let selection = self.selection();
+
+            if status == event::Status::Ignored && state.focused && !selection.is_empty() {
+                self.publish(shell, GraphEvent::DeleteRequested(selection));
+                status = event::Status::Captured;
+            }
+        }
+
+        if let Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: key_code @ (keyboard::KeyCode::M | keyboard::KeyCode::B),
+            modifiers,
+        }) = event
+        {
+            let mode = match key_code {
+                keyboard::KeyCode::M => Mode::Muted,
+                _ => Mode::Bypassed,
+            };
+            let modes = self.toggled_modes(mode);
+
+            if status == event::Status::Ignored
+                && state.focused
+                && !modifiers.command()
+                && !modes.is_empty()
+            {
+                self.publish(shell, GraphEvent::ModesRequested(modes));
+                status = event::Status::Captured;
+            }
+        }
+
         status
     }
 
@@ -283,17 +764,28 @@ where
         viewport: &Rectangle,
         renderer: &Renderer,
     ) -> mouse::Interaction {
-        self.content
-            .iter()
-            .zip(&tree.children)
-            .zip(layout.children())
-            .map(|((child, state), layout)| {
-                child
-                    .as_widget()
-                    .mouse_interaction(state, layout, cursor, viewport, renderer)
-            })
-            .max()
-            .unwrap_or_default()
+        let layouts: Vec<_> = layout.children().collect();
+        let mut cursor = cursor;
+
+        for i in self.order().into_iter().rev() {
+            let child = &self.content[i];
+            let interaction = child.as_widget().mouse_interaction(
+                &tree.children[i],
+                layouts[i],
+                cursor,
+                viewport,
+                renderer,
+            );
+
+            if interaction != mouse::Interaction::Idle {
+                return interaction;
+            }
+            if child.as_scalable_widget().is_obstacle() && cursor.is_over(layouts[i].bounds()) {
+                cursor = mouse::Cursor::Unavailable;
+            }
+        }
+
+        mouse::Interaction::Idle
     }
 
     fn draw(
@@ -353,9 +845,9 @@ where
                 style.major_guidelines_color.unwrap(),
             );
 
-            let mut children_layout = layout.children();
-            for i in 0..self.content.len() {
-                let layout = children_layout.next().unwrap();
+            let layouts: Vec<_> = layout.children().collect();
+            for i in self.order() {
+                let layout = layouts[i];
                 let node = self.content[i].as_widget();
 
                 let child_bounds = layout.bounds();
@@ -378,26 +870,59 @@ where
                     renderer_style,
                     layout,
                     cursor,
-                    &viewport,
+                    viewport,
                 );
             }
+
+            let hover = state
+                .state
+                .downcast_ref::<GraphContainerState<Id>>()
+                .tooltip
+                .as_ref()
+                .filter(|hover| hover.since.elapsed() >= self.tooltip_delay);
+
+            if let (Some(hover), Some(position)) = (hover, cursor.position_over(bounds)) {
+                let (i, tooltip) = hover.target;
+
+                if self.tooltip_at(layout, position) == Some(hover.target) {
+                    self.content[i].as_scalable_widget().draw_tooltip(
+                        &state.children[i],
+                        renderer,
+                        theme,
+                        TooltipStyle {
+                            background: style
+                                .tooltip_background
+                                .unwrap_or(Background::Color(Color::WHITE)),
+                            border_color: style.tooltip_border_color.unwrap_or(Color::BLACK),
+                            text_color: style
+                                .tooltip_text_color
+                                .unwrap_or(renderer_style.text_color),
+                        },
+                        layouts[i],
+                        tooltip,
+                        position,
+                        bounds,
+                    );
+                }
+            }
         });
     }
 }
 
-impl<'a, Message, Renderer> From<GraphContainer<'a, Message, Renderer>>
+impl<'a, Id, Message, Renderer> From<GraphContainer<'a, Id, Message, Renderer>>
     for Element<'a, Message, Renderer>
 where
+    Id: Clone + PartialEq + 'static,
     Message: 'a,
     Renderer: renderer::Renderer + 'a,
     Renderer::Theme: StyleSheet,
 {
-    fn from(graph_container: GraphContainer<'a, Message, Renderer>) -> Self {
+    fn from(graph_container: GraphContainer<'a, Id, Message, Renderer>) -> Self {
         Self::new(graph_container)
     }
 }
 
-fn draw_background<'a, Renderer>(renderer: &mut Renderer, bounds: Rectangle, style: Appearance)
+fn draw_background<Renderer>(renderer: &mut Renderer, bounds: Rectangle, style: Appearance)
 where
     Renderer: renderer::Renderer,
 {
@@ -414,7 +939,7 @@ where
     );
 }
 
-fn draw_guidelines<'a, Renderer>(
+fn draw_guidelines<Renderer>(
     renderer: &mut Renderer,
     bounds: Rectangle,
     offset: (f32, f32),
@@ -491,12 +1016,122 @@ fn draw_guidelines<'a, Renderer>(
     }
 }
 
+/// The source and target nodes of a connection.
+type Wire<Id> = (Id, Id);
+
+/// The wires of `current` missing from `previous`, and those of `previous` missing
+/// from `current`, counting wires linking the same nodes separately.
+fn changed_wires<Id: Clone + PartialEq>(
+    previous: &[Wire<Id>],
+    current: &[Wire<Id>],
+) -> (Vec<Wire<Id>>, Vec<Wire<Id>>) {
+    let mut removed = previous.to_vec();
+    let mut added = Vec::new();
+
+    for wire in current {
+        match removed.iter().position(|other| other == wire) {
+            Some(index) => {
+                removed.remove(index);
+            }
+            None => added.push(wire.clone()),
+        }
+    }
+
+    (added, removed)
+}
+
+fn same_measurements<Id: PartialEq>(a: &[(Id, Size)], b: &[(Id, Size)]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|((a_id, a_size), (b_id, b_size))| {
+            a_id == b_id
+                && (a_size.width - b_size.width).abs() < 0.5
+                && (a_size.height - b_size.height).abs() < 0.5
+        })
+}
+
 fn normalize_scale(scale: f32) -> f32 {
     let log_2 = scale.log2().floor();
 
     if log_2.abs() > f32::EPSILON {
-        return scale / 2.0_f32.powf(log_2);
+        scale / 2.0_f32.powf(log_2)
     } else {
-        return scale;
+        scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::widget::text;
+
+    use super::*;
+    use crate::{connection, node};
+
+    fn graph(modes: &[(Mode, bool)]) -> GraphContainer<'static, usize, (), iced::Renderer> {
+        graph_container(
+            modes
+                .iter()
+                .enumerate()
+                .map(|(id, (mode, selected))| {
+                    node(text(id)).id(id).mode(*mode).selected(*selected).into()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn toggling_a_mode_applies_it_to_the_selection() {
+        let graph = graph(&[
+            (Mode::Active, true),
+            (Mode::Bypassed, true),
+            (Mode::Active, false),
+        ]);
+
+        assert_eq!(
+            graph.toggled_modes(Mode::Muted),
+            [(0, Mode::Muted), (1, Mode::Muted)]
+        );
+    }
+
+    #[test]
+    fn wires_link_the_nodes_of_port_to_port_connections() {
+        let graph: GraphContainer<'_, usize, (), iced::Renderer> = graph_container(vec![
+            node(text("a")).id(0).into(),
+            node(text("b")).id(1).into(),
+            connection(Endpoint::Output(0, 0), Endpoint::Input(1, 0)).into(),
+            connection(Endpoint::Input(0, 1), Endpoint::Output(1, 0)).into(),
+            connection(Endpoint::Output(1, 0), Endpoint::Point(Point::ORIGIN)).into(),
+        ]);
+
+        assert_eq!(graph.wires(), [(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn changed_wires_are_told_apart_from_kept_ones() {
+        let previous = [(0, 1), (1, 2), (1, 2)];
+        let current = [(1, 2), (2, 0), (0, 1), (2, 0)];
+
+        let (added, removed) = changed_wires(&previous, &current);
+
+        assert_eq!(added, [(2, 0), (2, 0)]);
+        assert_eq!(removed, [(1, 2)]);
+        assert_eq!(changed_wires(&current, &current), (vec![], vec![]));
+    }
+
+    #[test]
+    fn toggling_a_mode_twice_reactivates() {
+        let graph = graph(&[
+            (Mode::Muted, true),
+            (Mode::Muted, true),
+            (Mode::Muted, false),
+        ]);
+
+        assert_eq!(
+            graph.toggled_modes(Mode::Muted),
+            [(0, Mode::Active), (1, Mode::Active)]
+        );
+        assert_eq!(
+            graph.toggled_modes(Mode::Bypassed),
+            [(0, Mode::Bypassed), (1, Mode::Bypassed)]
+        );
     }
 }
@@ -0,0 +1,43 @@
+use iced::{Size, Vector};
+
+use crate::{Matrix, Mode};
+
+/// An interaction reported by a [`GraphContainer`](crate::GraphContainer).
+///
+/// Nodes are identified by the id attached to them with [`Node::id`](crate::Node::id).
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphEvent<Id> {
+    /// A node was dragged by `delta`, expressed in graph space.
+    NodeMoved {
+        id: Id,
+        delta: Vector,
+    },
+    /// A node was pressed and released without being dragged further than the
+    /// drag threshold.
+    NodeClicked(Id),
+    /// A node was dragged further than the drag threshold.
+    NodeDragStarted(Id),
+    NodeDragEnded(Id),
+    /// A connection from an output of node `from` to an input of node `to` was added
+    /// to the container.
+    Connected {
+        from: Id,
+        to: Id,
+    },
+    /// A connection from node `from` to node `to` was removed from the container.
+    Disconnected {
+        from: Id,
+        to: Id,
+    },
+    /// The full set of selected nodes after a click.
+    SelectionChanged(Vec<Id>),
+    /// The camera was panned or zoomed, carrying the new transform.
+    CameraChanged(Matrix),
+    /// The user asked to delete the selected nodes.
+    DeleteRequested(Vec<Id>),
+    /// The graph-space size of every node with an id, sent whenever it changes.
+    NodesMeasured(Vec<(Id, Size)>),
+    /// The user toggled the [`Mode`] of the selected nodes, `M` muting them and `B`
+    /// bypassing them, carrying the mode each node should be given.
+    ModesRequested(Vec<(Id, Mode)>),
+}
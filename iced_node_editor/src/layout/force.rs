@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use iced::{Point, Size, Vector};
+
+/// Settings for [`ForceLayout`].
+#[derive(Debug, Clone, Copy)]
+pub struct ForceOptions {
+    /// Gap the springs try to keep between the borders of two linked nodes.
+    pub spring_length: f32,
+    /// How strongly linked nodes are pulled towards `spring_length`.
+    pub spring_strength: f32,
+    /// How strongly every pair of nodes pushes each other away.
+    pub repulsion: f32,
+    /// Pull towards the center of the graph, keeping components together.
+    pub gravity: f32,
+    /// Fraction of the velocity kept from one step to the next.
+    pub damping: f32,
+    /// Minimum gap kept between the bounds of any two nodes.
+    pub padding: f32,
+    /// Largest distance a node may travel in a single step.
+    pub max_step: f32,
+    /// The layout is settled once no node moves further than this in a step.
+    pub tolerance: f32,
+    /// Upper bound on the number of steps taken by [`ForceLayout::run`].
+    pub max_iterations: usize,
+}
+
+impl Default for ForceOptions {
+    fn default() -> Self {
+        Self {
+            spring_length: 80.0,
+            spring_strength: 0.05,
+            repulsion: 20000.0,
+            gravity: 0.01,
+            damping: 0.8,
+            padding: 20.0,
+            max_step: 40.0,
+            tolerance: 0.1,
+            max_iterations: 1000,
+        }
+    }
+}
+
+/// A spring and repulsion simulation over a set of nodes.
+///
+/// Call [`run`](Self::run) to settle the layout at once, or
+/// [`step`](Self::step) once per frame to let the nodes visibly move into place.
+pub struct ForceLayout<Id> {
+    ids: Vec<Id>,
+    centers: Vec<Point>,
+    sizes: Vec<Size>,
+    velocities: Vec<Vector>,
+    pinned: Vec<bool>,
+    links: Vec<(usize, usize)>,
+    options: ForceOptions,
+    last_movement: f32,
+}
+
+impl<Id> ForceLayout<Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    /// Creates a simulation from the current top-left `position` and `size` of
+    /// every node. Edges referring to unknown ids are ignored.
+    pub fn new(nodes: &[(Id, Point, Size)], edges: &[(Id, Id)], options: ForceOptions) -> Self {
+        let index: HashMap<&Id, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _, _))| (id, i))
+            .collect();
+
+        let links = edges
+            .iter()
+            .filter_map(|(from, to)| Some((*index.get(from)?, *index.get(to)?)))
+            .filter(|(from, to)| from != to)
+            .collect();
+
+        Self {
+            ids: nodes.iter().map(|(id, _, _)| id.clone()).collect(),
+            centers: nodes
+                .iter()
+                .map(|(_, position, size)| {
+                    Point::new(
+                        position.x + size.width / 2.0,
+                        position.y + size.height / 2.0,
+                    )
+                })
+                .collect(),
+            sizes: nodes.iter().map(|(_, _, size)| *size).collect(),
+            velocities: vec![Vector::new(0.0, 0.0); nodes.len()],
+            pinned: vec![false; nodes.len()],
+            links,
+            options,
+            last_movement: f32::MAX,
+        }
+    }
+
+    /// Keeps the node in place while the others move around it.
+    pub fn pin(&mut self, id: &Id, pinned: bool) {
+        if let Some(i) = self.ids.iter().position(|other| other == id) {
+            self.pinned[i] = pinned;
+            self.velocities[i] = Vector::new(0.0, 0.0);
+        }
+    }
+
+    /// Moves a node, e.g. to follow the user dragging it during the simulation.
+    pub fn set_position(&mut self, id: &Id, position: Point) {
+        if let Some(i) = self.ids.iter().position(|other| other == id) {
+            self.centers[i] = Point::new(
+                position.x + self.sizes[i].width / 2.0,
+                position.y + self.sizes[i].height / 2.0,
+            );
+            self.velocities[i] = Vector::new(0.0, 0.0);
+            self.last_movement = f32::MAX;
+        }
+    }
+
+    /// Advances the simulation once and returns the largest distance a node moved.
+    pub fn step(&mut self) -> f32 {
+        let count = self.centers.len();
+        let mut forces = vec![Vector::new(0.0, 0.0); count];
+
+        for i in 0..count {
+            for j in i + 1..count {
+                let (direction, distance) = separation(self.centers[i], self.centers[j], i, j);
+                let distance = distance.max(1.0);
+                let push = direction * (self.options.repulsion / (distance * distance));
+
+                forces[i] = forces[i] + push;
+                forces[j] = forces[j] - push;
+            }
+        }
+
+        for (from, to) in &self.links {
+            let (direction, distance) =
+                separation(self.centers[*to], self.centers[*from], *to, *from);
+            let rest = self.options.spring_length
+                + radius(self.sizes[*from], direction)
+                + radius(self.sizes[*to], direction);
+            let pull = direction * ((distance - rest) * self.options.spring_strength);
+
+            forces[*from] = forces[*from] + pull;
+            forces[*to] = forces[*to] - pull;
+        }
+
+        if count > 0 {
+            let center = self
+                .centers
+                .iter()
+                .fold(Vector::new(0.0, 0.0), |sum, c| sum + Vector::new(c.x, c.y))
+                * (1.0 / count as f32);
+
+            for (force, position) in forces.iter_mut().zip(&self.centers) {
+                let offset = Vector::new(center.x - position.x, center.y - position.y);
+                *force = *force + offset * self.options.gravity;
+            }
+        }
+
+        let previous = self.centers.clone();
+
+        for (((center, velocity), force), pinned) in self
+            .centers
+            .iter_mut()
+            .zip(&mut self.velocities)
+            .zip(forces)
+            .zip(&self.pinned)
+        {
+            if *pinned {
+                continue;
+            }
+
+            *velocity = (*velocity + force) * self.options.damping;
+            let speed = length(*velocity);
+            if speed > self.options.max_step {
+                *velocity = *velocity * (self.options.max_step / speed);
+            }
+
+            *center = *center + *velocity;
+        }
+
+        self.resolve_collisions();
+
+        let movement = self
+            .centers
+            .iter()
+            .zip(previous)
+            .map(|(center, previous)| length(*center - previous))
+            .fold(0.0, f32::max);
+        self.last_movement = movement;
+
+        movement
+    }
+
+    /// Steps until the layout settles or `max_iterations` is reached, returning
+    /// the number of steps taken.
+    pub fn run(&mut self) -> usize {
+        let mut iterations = 0;
+
+        while iterations < self.options.max_iterations && !self.is_settled() {
+            self.step();
+            iterations += 1;
+        }
+
+        iterations
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.last_movement < self.options.tolerance
+    }
+
+    /// The top-left corner of every node, ready for [`Node::position`](crate::Node::position).
+    pub fn positions(&self) -> HashMap<Id, Point> {
+        self.ids
+            .iter()
+            .zip(&self.centers)
+            .zip(&self.sizes)
+            .map(|((id, center), size)| {
+                (
+                    id.clone(),
+                    Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+                )
+            })
+            .collect()
+    }
+
+    /// Pushes overlapping nodes apart along the axis of least overlap,
+    /// cancelling the velocity that drove them into each other.
+    fn resolve_collisions(&mut self) {
+        let count = self.centers.len();
+
+        for i in 0..count {
+            for j in i + 1..count {
+                if self.pinned[i] && self.pinned[j] {
+                    continue;
+                }
+
+                let dx = self.centers[j].x - self.centers[i].x;
+                let dy = self.centers[j].y - self.centers[i].y;
+                let overlap_x = (self.sizes[i].width + self.sizes[j].width) / 2.0
+                    + self.options.padding
+                    - dx.abs();
+                let overlap_y = (self.sizes[i].height + self.sizes[j].height) / 2.0
+                    + self.options.padding
+                    - dy.abs();
+
+                if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                    continue;
+                }
+
+                let (direction, _) = separation(self.centers[i], self.centers[j], i, j);
+                let horizontal = overlap_x < overlap_y;
+                let push = if horizontal {
+                    Vector::new(overlap_x * sign(dx, -direction.x), 0.0)
+                } else {
+                    Vector::new(0.0, overlap_y * sign(dy, -direction.y))
+                };
+
+                let (share_i, share_j) = match (self.pinned[i], self.pinned[j]) {
+                    (true, _) => (0.0, 1.0),
+                    (_, true) => (1.0, 0.0),
+                    _ => (0.5, 0.5),
+                };
+
+                self.centers[i] = self.centers[i] - push * share_i;
+                self.centers[j] = self.centers[j] + push * share_j;
+
+                for k in [i, j] {
+                    if horizontal {
+                        self.velocities[k].x = 0.0;
+                    } else {
+                        self.velocities[k].y = 0.0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Settles a force-directed layout in one call, leaving the `pinned` nodes
+/// where they are.
+pub fn force_directed<Id>(
+    nodes: &[(Id, Point, Size)],
+    edges: &[(Id, Id)],
+    pinned: &[Id],
+    options: ForceOptions,
+) -> HashMap<Id, Point>
+where
+    Id: Clone + Eq + Hash,
+{
+    let mut layout = ForceLayout::new(nodes, edges, options);
+    for id in pinned {
+        layout.pin(id, true);
+    }
+
+    layout.run();
+    layout.positions()
+}
+
+/// Unit vector pointing from `b` to `a` and the distance between them.
+///
+/// Nodes sharing a position, e.g. freshly imported ones, are spread apart along
+/// a direction derived from their indices so the simulation can untangle them.
+fn separation(a: Point, b: Point, i: usize, j: usize) -> (Vector, f32) {
+    let delta = Vector::new(a.x - b.x, a.y - b.y);
+    let distance = length(delta);
+
+    if distance > f32::EPSILON {
+        (delta * (1.0 / distance), distance)
+    } else {
+        let angle = (i * 31 + j * 17) as f32 * 2.399_963;
+        (Vector::new(angle.cos(), angle.sin()), 0.0)
+    }
+}
+
+/// Distance from the center of a box of `size` to its border along `direction`.
+fn radius(size: Size, direction: Vector) -> f32 {
+    let x = if direction.x.abs() > f32::EPSILON {
+        size.width / 2.0 / direction.x.abs()
+    } else {
+        f32::MAX
+    };
+    let y = if direction.y.abs() > f32::EPSILON {
+        size.height / 2.0 / direction.y.abs()
+    } else {
+        f32::MAX
+    };
+
+    x.min(y)
+}
+
+fn sign(value: f32, fallback: f32) -> f32 {
+    if value.abs() > f32::EPSILON {
+        value.signum()
+    } else {
+        fallback.signum()
+    }
+}
+
+fn length(vector: Vector) -> f32 {
+    (vector.x * vector.x + vector.y * vector.y).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Size = Size::new(100.0, 60.0);
+
+    fn options() -> ForceOptions {
+        ForceOptions {
+            max_iterations: 500,
+            ..ForceOptions::default()
+        }
+    }
+
+    #[test]
+    fn linked_nodes_settle_near_the_spring_length() {
+        let nodes = [
+            (0, Point::new(0.0, 0.0), SIZE),
+            (1, Point::new(1500.0, 40.0), SIZE),
+        ];
+        let mut layout = ForceLayout::new(&nodes, &[(0, 1)], options());
+        for _ in 0..300 {
+            layout.step();
+        }
+        let positions = layout.positions();
+
+        // The nodes sit side by side, so the gap between their borders is horizontal.
+        let gap = (positions[&1].x - positions[&0].x).abs() - SIZE.width;
+        assert!(gap > 0.0, "gap {gap}");
+        assert!(gap < options().spring_length * 1.5, "gap {gap}");
+    }
+
+    #[test]
+    fn unlinked_nodes_on_the_same_point_are_spread_apart() {
+        let nodes: Vec<_> = (0..4).map(|i| (i, Point::ORIGIN, SIZE)).collect();
+        let positions = force_directed(&nodes, &[], &[], options());
+
+        for a in 0..4 {
+            for b in a + 1..4 {
+                let (a, b) = (positions[&a], positions[&b]);
+                let apart = (a.x - b.x).abs() >= SIZE.width || (a.y - b.y).abs() >= SIZE.height;
+                assert!(apart, "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn pinned_nodes_stay_in_place() {
+        let nodes = [
+            (0, Point::new(10.0, 20.0), SIZE),
+            (1, Point::new(600.0, 20.0), SIZE),
+        ];
+        let positions = force_directed(&nodes, &[(0, 1)], &[0], options());
+
+        assert_eq!(positions[&0], Point::new(10.0, 20.0));
+    }
+}
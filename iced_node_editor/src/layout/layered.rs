@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use iced::{Point, Size};
+
+/// Settings for [`layered`].
+#[derive(Debug, Clone, Copy)]
+pub struct LayeredOptions {
+    /// Horizontal gap between two consecutive layers.
+    pub layer_spacing: f32,
+    /// Vertical gap between two nodes of the same layer.
+    pub node_spacing: f32,
+    /// Number of barycenter sweeps used to reduce edge crossings.
+    pub sweeps: usize,
+}
+
+impl Default for LayeredOptions {
+    fn default() -> Self {
+        Self {
+            layer_spacing: 80.0,
+            node_spacing: 30.0,
+            sweeps: 8,
+        }
+    }
+}
+
+/// Places nodes in left-to-right layers following the direction of `edges`.
+///
+/// `nodes` pairs every id with its size in graph space. Edges referring to
+/// unknown ids are ignored and cycles are broken by reversing back edges.
+/// The returned positions are the top-left corners to pass to
+/// [`Node::position`](crate::Node::position).
+pub fn layered<Id>(
+    nodes: &[(Id, Size)],
+    edges: &[(Id, Id)],
+    options: &LayeredOptions,
+) -> HashMap<Id, Point>
+where
+    Id: Clone + Eq + Hash,
+{
+    let index: HashMap<&Id, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (id, i))
+        .collect();
+
+    let links: Vec<(usize, usize)> = edges
+        .iter()
+        .filter_map(|(from, to)| Some((*index.get(from)?, *index.get(to)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+
+    let links = remove_cycles(nodes.len(), &links);
+    let mut layer_of = assign_layers(nodes.len(), &links);
+    let mut sizes: Vec<Size> = nodes.iter().map(|(_, size)| *size).collect();
+
+    // Edges spanning several layers are split with zero sized dummy nodes so
+    // that every segment connects two adjacent layers.
+    let mut segments = Vec::new();
+    for (from, to) in links {
+        let mut previous = from;
+        for layer in layer_of[from] + 1..layer_of[to] {
+            let dummy = sizes.len();
+            sizes.push(Size::ZERO);
+            layer_of.push(layer);
+            segments.push((previous, dummy));
+            previous = dummy;
+        }
+        segments.push((previous, to));
+    }
+
+    let mut predecessors = vec![Vec::new(); sizes.len()];
+    let mut successors = vec![Vec::new(); sizes.len()];
+    for (from, to) in &segments {
+        predecessors[*to].push(*from);
+        successors[*from].push(*to);
+    }
+
+    let layer_count = layer_of.iter().max().map_or(0, |layer| layer + 1);
+    let mut layers = vec![Vec::new(); layer_count];
+    for (node, layer) in layer_of.iter().enumerate() {
+        layers[*layer].push(node);
+    }
+
+    order_layers(&mut layers, &predecessors, &successors, options.sweeps);
+
+    let xs = assign_x(&layers, &sizes, options.layer_spacing);
+    let ys = assign_y(
+        &layers,
+        &sizes,
+        &predecessors,
+        &successors,
+        options.node_spacing,
+    );
+
+    let top = (0..nodes.len())
+        .map(|node| ys[node])
+        .fold(f32::MAX, f32::min);
+
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(node, (id, _))| (id.clone(), Point::new(xs[node], ys[node] - top)))
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    Active,
+    Done,
+}
+
+fn remove_cycles(count: usize, links: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut outgoing = vec![Vec::new(); count];
+    for (link, (from, _)) in links.iter().enumerate() {
+        outgoing[*from].push(link);
+    }
+
+    let mut visit = vec![Visit::New; count];
+    let mut reversed = vec![false; links.len()];
+
+    for root in 0..count {
+        if visit[root] != Visit::New {
+            continue;
+        }
+
+        visit[root] = Visit::Active;
+        let mut stack = vec![(root, 0)];
+
+        while let Some(&(node, next)) = stack.last() {
+            if let Some(&link) = outgoing[node].get(next) {
+                stack.last_mut().unwrap().1 += 1;
+
+                let target = links[link].1;
+                match visit[target] {
+                    Visit::New => {
+                        visit[target] = Visit::Active;
+                        stack.push((target, 0));
+                    }
+                    Visit::Active => reversed[link] = true,
+                    Visit::Done => {}
+                }
+            } else {
+                visit[node] = Visit::Done;
+                stack.pop();
+            }
+        }
+    }
+
+    let mut result: Vec<(usize, usize)> = links
+        .iter()
+        .zip(reversed)
+        .map(|(&(from, to), reversed)| if reversed { (to, from) } else { (from, to) })
+        .collect();
+
+    result.sort_unstable();
+    result.dedup();
+    result
+}
+
+fn assign_layers(count: usize, links: &[(usize, usize)]) -> Vec<usize> {
+    let mut incoming = vec![0; count];
+    let mut outgoing = vec![Vec::new(); count];
+    for (from, to) in links {
+        incoming[*to] += 1;
+        outgoing[*from].push(*to);
+    }
+
+    let mut layer = vec![0; count];
+    let mut ready: Vec<usize> = (0..count).filter(|node| incoming[*node] == 0).collect();
+
+    while let Some(node) = ready.pop() {
+        for target in &outgoing[node] {
+            layer[*target] = layer[*target].max(layer[node] + 1);
+            incoming[*target] -= 1;
+            if incoming[*target] == 0 {
+                ready.push(*target);
+            }
+        }
+    }
+
+    layer
+}
+
+fn order_layers(
+    layers: &mut Vec<Vec<usize>>,
+    predecessors: &[Vec<usize>],
+    successors: &[Vec<usize>],
+    sweeps: usize,
+) {
+    let mut best = layers.clone();
+    let mut best_crossings = count_crossings(layers, successors);
+
+    for sweep in 0..sweeps {
+        if best_crossings == 0 {
+            break;
+        }
+
+        if sweep % 2 == 0 {
+            for layer in 1..layers.len() {
+                reorder(layers, layer, layer - 1, predecessors);
+            }
+        } else {
+            for layer in (0..layers.len().saturating_sub(1)).rev() {
+                reorder(layers, layer, layer + 1, successors);
+            }
+        }
+
+        let crossings = count_crossings(layers, successors);
+        if crossings < best_crossings {
+            best = layers.clone();
+            best_crossings = crossings;
+        }
+    }
+
+    *layers = best;
+}
+
+fn reorder(layers: &mut [Vec<usize>], layer: usize, fixed: usize, neighbours: &[Vec<usize>]) {
+    let rank: HashMap<usize, usize> = layers[fixed]
+        .iter()
+        .enumerate()
+        .map(|(rank, node)| (*node, rank))
+        .collect();
+
+    let mut keyed: Vec<(f32, usize)> = layers[layer]
+        .iter()
+        .enumerate()
+        .map(|(current, node)| {
+            let ranks: Vec<usize> = neighbours[*node]
+                .iter()
+                .filter_map(|neighbour| rank.get(neighbour).copied())
+                .collect();
+
+            if ranks.is_empty() {
+                (current as f32, *node)
+            } else {
+                let sum: usize = ranks.iter().sum();
+                (sum as f32 / ranks.len() as f32, *node)
+            }
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    layers[layer] = keyed.into_iter().map(|(_, node)| node).collect();
+}
+
+fn count_crossings(layers: &[Vec<usize>], successors: &[Vec<usize>]) -> usize {
+    let mut crossings = 0;
+
+    for pair in layers.windows(2) {
+        let rank: HashMap<usize, usize> = pair[1]
+            .iter()
+            .enumerate()
+            .map(|(rank, node)| (*node, rank))
+            .collect();
+
+        let segments: Vec<(usize, usize)> = pair[0]
+            .iter()
+            .enumerate()
+            .flat_map(|(upper, node)| {
+                successors[*node]
+                    .iter()
+                    .filter_map(|target| rank.get(target).map(|lower| (upper, *lower)))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        for (i, a) in segments.iter().enumerate() {
+            for b in &segments[i + 1..] {
+                if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                    crossings += 1;
+                }
+            }
+        }
+    }
+
+    crossings
+}
+
+fn assign_x(layers: &[Vec<usize>], sizes: &[Size], layer_spacing: f32) -> Vec<f32> {
+    let mut xs = vec![0.0; sizes.len()];
+    let mut left = 0.0;
+
+    for layer in layers {
+        let width = layer
+            .iter()
+            .map(|node| sizes[*node].width)
+            .fold(0.0, f32::max);
+
+        for node in layer {
+            xs[*node] = left + (width - sizes[*node].width) / 2.0;
+        }
+
+        left += width + layer_spacing;
+    }
+
+    xs
+}
+
+/// Returns the top edge of every node, keeping the order chosen for each
+/// layer while pulling nodes towards the center of their neighbours.
+fn assign_y(
+    layers: &[Vec<usize>],
+    sizes: &[Size],
+    predecessors: &[Vec<usize>],
+    successors: &[Vec<usize>],
+    node_spacing: f32,
+) -> Vec<f32> {
+    let mut centers = vec![0.0; sizes.len()];
+
+    for layer in layers {
+        let mut top = 0.0;
+        for node in layer {
+            centers[*node] = top + sizes[*node].height / 2.0;
+            top += sizes[*node].height + node_spacing;
+        }
+    }
+
+    for round in 0..4 {
+        let downwards = round % 2 == 0;
+        let neighbours = if downwards { predecessors } else { successors };
+
+        let order: Vec<usize> = if downwards {
+            (0..layers.len()).collect()
+        } else {
+            (0..layers.len()).rev().collect()
+        };
+
+        for layer in order {
+            let desired: Vec<f32> = layers[layer]
+                .iter()
+                .map(|node| {
+                    let adjacent = &neighbours[*node];
+                    if adjacent.is_empty() {
+                        centers[*node]
+                    } else {
+                        adjacent.iter().map(|n| centers[*n]).sum::<f32>() / adjacent.len() as f32
+                    }
+                })
+                .collect();
+
+            let mut bottom = f32::MIN;
+            for (node, desired) in layers[layer].iter().zip(&desired) {
+                let half = sizes[*node].height / 2.0;
+                let center = (desired - half).max(bottom + node_spacing) + half;
+                centers[*node] = center;
+                bottom = center + half;
+            }
+
+            // Sliding the whole layer keeps the gaps while balancing the
+            // displacement introduced by the forward pass.
+            let shift = layers[layer]
+                .iter()
+                .zip(&desired)
+                .map(|(node, desired)| centers[*node] - desired)
+                .sum::<f32>()
+                / layers[layer].len().max(1) as f32;
+
+            for node in &layers[layer] {
+                centers[*node] -= shift;
+            }
+        }
+    }
+
+    centers
+        .iter()
+        .zip(sizes)
+        .map(|(center, size)| center - size.height / 2.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: (Point, Size), b: (Point, Size)) -> bool {
+        a.0.x < b.0.x + b.1.width
+            && b.0.x < a.0.x + a.1.width
+            && a.0.y < b.0.y + b.1.height
+            && b.0.y < a.0.y + a.1.height
+    }
+
+    #[test]
+    fn edges_point_to_a_later_layer_after_breaking_cycles() {
+        let links = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 1), (4, 2)];
+        let acyclic = remove_cycles(5, &links);
+        let layer = assign_layers(5, &acyclic);
+
+        assert_eq!(acyclic.len(), links.len());
+        for (from, to) in acyclic {
+            assert!(layer[to] > layer[from], "{from} -> {to} in {layer:?}");
+        }
+    }
+
+    #[test]
+    fn barycenter_sweeps_remove_crossings() {
+        let mut layers = vec![vec![0, 1], vec![2, 3]];
+        let successors = vec![vec![3], vec![2], vec![], vec![]];
+        let predecessors = vec![vec![], vec![], vec![1], vec![0]];
+
+        assert_eq!(count_crossings(&layers, &successors), 1);
+        order_layers(&mut layers, &predecessors, &successors, 4);
+        assert_eq!(count_crossings(&layers, &successors), 0);
+    }
+
+    #[test]
+    fn nodes_do_not_overlap_and_edges_go_right() {
+        let nodes: Vec<(usize, Size)> = (0..7)
+            .map(|i| (i, Size::new(40.0 + i as f32 * 10.0, 30.0 + i as f32 * 5.0)))
+            .collect();
+        let edges = [
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (2, 3),
+            (0, 3),
+            (3, 4),
+            (5, 6),
+            (4, 0),
+        ];
+        let positions = layered(&nodes, &edges, &LayeredOptions::default());
+
+        assert_eq!(positions.len(), nodes.len());
+        for (i, (a, size_a)) in nodes.iter().enumerate() {
+            for (b, size_b) in &nodes[i + 1..] {
+                assert!(
+                    !overlaps((positions[a], *size_a), (positions[b], *size_b)),
+                    "{a} overlaps {b}"
+                );
+            }
+        }
+
+        // Apart from the edge closing the cycle, every edge goes to a layer further right.
+        for (from, to) in &edges[..edges.len() - 1] {
+            assert!(positions[to].x >= positions[from].x + nodes[*from].1.width);
+        }
+    }
+}
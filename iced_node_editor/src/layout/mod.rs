@@ -0,0 +1,5 @@
+mod force;
+mod layered;
+
+pub use force::{force_directed, ForceLayout, ForceOptions};
+pub use layered::{layered, LayeredOptions};
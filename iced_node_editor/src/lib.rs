@@ -1,17 +1,30 @@
 mod graph_container;
 pub mod styles;
+pub mod layout;
+pub mod model;
+pub mod dot;
+pub mod mermaid;
 mod matrix;
 mod node;
+mod port;
 mod node_element;
 mod mesh_renderer;
 mod connection;
+mod routing;
+mod tessellation;
+mod tooltip;
+mod graph_event;
 
 pub use matrix::Matrix;
 
 pub use graph_container::graph_container;
-pub use node::node;
-pub use connection::connection;
+pub use node::{node, DragRegion, Mode, Status};
+pub use port::{port, Endpoint, Port};
+pub use connection::{connection, Flow, Marker};
+pub use routing::{ConnectionRouting, RouteCache};
+pub use tessellation::{LineCap, LineJoin};
 
 pub use node_element::GraphNodeElement;
 pub use node::Node;
-pub use graph_container::GraphContainer;
\ No newline at end of file
+pub use graph_container::GraphContainer;
+pub use graph_event::GraphEvent;
\ No newline at end of file
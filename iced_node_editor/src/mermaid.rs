@@ -0,0 +1,861 @@
+//! Reading and writing Mermaid flowcharts.
+//!
+//! Mermaid has no ports or positions, so both are dropped on export. Use
+//! [`parse_and_arrange`] to get a graph that is ready to be shown in a
+//! [`GraphContainer`](crate::GraphContainer).
+
+use std::collections::{HashMap, HashSet};
+
+use iced::Size;
+
+use crate::layout::LayeredOptions;
+use crate::model::{Graph, GraphEdge, GraphNode, ParseError, Subgraph};
+
+/// Node shapes as `(name, opening, closing)`, longest delimiters first so
+/// that prefixes are tried last.
+const SHAPES: [(&str, &str, &str); 14] = [
+    ("double_circle", "(((", ")))"),
+    ("circle", "((", "))"),
+    ("stadium", "([", "])"),
+    ("subroutine", "[[", "]]"),
+    ("cylinder", "[(", ")]"),
+    ("hexagon", "{{", "}}"),
+    ("parallelogram", "[/", "/]"),
+    ("trapezoid", "[/", "\\]"),
+    ("parallelogram_alt", "[\\", "\\]"),
+    ("trapezoid_alt", "[\\", "/]"),
+    ("round", "(", ")"),
+    ("rect", "[", "]"),
+    ("rhombus", "{", "}"),
+    ("asymmetric", ">", "]"),
+];
+
+/// Shape used for nodes without a `shape` attribute.
+const DEFAULT_SHAPE: (&str, &str, &str) = ("rect", "[", "]");
+
+type Attributes = Vec<(String, String)>;
+
+const RESERVED: [&str; 10] = [
+    "end",
+    "subgraph",
+    "graph",
+    "flowchart",
+    "direction",
+    "style",
+    "class",
+    "classDef",
+    "click",
+    "linkStyle",
+];
+
+/// Writes `graph` as a `flowchart LR` diagram.
+pub fn export(graph: &Graph) -> String {
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    let mut used: HashSet<String> = HashSet::new();
+
+    let names = graph
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .chain(graph.subgraphs.iter().map(|subgraph| subgraph.id.as_str()));
+
+    for (index, id) in names.enumerate() {
+        if ids.contains_key(id) {
+            continue;
+        }
+
+        let mut name = if is_identifier(id) {
+            id.to_owned()
+        } else {
+            format!("n{}", index)
+        };
+        while used.contains(&name) {
+            name.push('_');
+        }
+
+        used.insert(name.clone());
+        ids.insert(id, name);
+    }
+
+    let mut out = String::from("flowchart LR\n");
+
+    let grouped: HashSet<&str> = graph
+        .subgraphs
+        .iter()
+        .flat_map(|subgraph| subgraph.nodes.iter().map(String::as_str))
+        .collect();
+
+    for node in &graph.nodes {
+        if !grouped.contains(node.id.as_str()) {
+            push_node(&mut out, node, &ids, 1);
+        }
+    }
+
+    for subgraph in graph.subgraphs.iter().filter(|s| s.parent.is_none()) {
+        push_subgraph(&mut out, graph, subgraph, &ids, 1);
+    }
+
+    for edge in &graph.edges {
+        let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str()))
+        else {
+            continue;
+        };
+
+        out.push_str("    ");
+        out.push_str(from);
+        out.push(' ');
+        out.push_str(&link(edge, graph.directed));
+        if let Some(label) = &edge.label {
+            out.push_str(&format!("|\"{}\"|", escape(label)));
+        }
+        out.push(' ');
+        out.push_str(to);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Reads a Mermaid `flowchart` or `graph` diagram, optionally wrapped in a
+/// ```` ```mermaid ```` fence.
+pub fn parse(text: &str) -> Result<Graph, ParseError> {
+    let mut parser = Parser {
+        graph: Graph::new(),
+        stack: Vec::new(),
+        header: false,
+    };
+
+    let mut last_line = 1;
+
+    for (index, line) in text.lines().enumerate() {
+        last_line = index + 1;
+
+        if line.trim_start().starts_with("```") {
+            continue;
+        }
+
+        for (column, statement) in statements(line) {
+            if statement.trim().is_empty() {
+                continue;
+            }
+
+            let mut cursor = Cursor {
+                chars: statement.chars().collect(),
+                index: 0,
+                line: index + 1,
+                column,
+            };
+
+            parser.statement(&mut cursor)?;
+        }
+    }
+
+    if !parser.header {
+        return Err(ParseError::new(
+            1,
+            1,
+            "expected a `flowchart` or `graph` header",
+        ));
+    }
+
+    if let Some(subgraph) = parser.stack.last() {
+        return Err(ParseError::new(
+            last_line,
+            1,
+            format!("subgraph `{}` is missing its `end`", subgraph),
+        ));
+    }
+
+    Ok(parser.graph)
+}
+
+/// Reads a Mermaid diagram like [`parse`] and positions its nodes with
+/// [`Graph::arrange`].
+pub fn parse_and_arrange(
+    text: &str,
+    size: impl Fn(&GraphNode) -> Size,
+    options: &LayeredOptions,
+) -> Result<Graph, ParseError> {
+    let mut graph = parse(text)?;
+    graph.arrange(size, options);
+    Ok(graph)
+}
+
+fn push_node(out: &mut String, node: &GraphNode, ids: &HashMap<&str, String>, depth: usize) {
+    let id = &ids[node.id.as_str()];
+    let shape = node
+        .attributes
+        .iter()
+        .find(|(key, _)| key == "shape")
+        .and_then(|(_, shape)| SHAPES.iter().find(|(name, _, _)| name == shape))
+        .unwrap_or(&DEFAULT_SHAPE);
+
+    out.push_str(&"    ".repeat(depth));
+    out.push_str(id);
+
+    let label = node.label.as_deref().unwrap_or(&node.id);
+    if label != id || shape.0 != DEFAULT_SHAPE.0 {
+        out.push_str(&format!("{}\"{}\"{}", shape.1, escape(label), shape.2));
+    }
+    out.push('\n');
+}
+
+fn push_subgraph(
+    out: &mut String,
+    graph: &Graph,
+    subgraph: &Subgraph,
+    ids: &HashMap<&str, String>,
+    depth: usize,
+) {
+    let indent = "    ".repeat(depth);
+    let id = &ids[subgraph.id.as_str()];
+
+    out.push_str(&format!("{}subgraph {}", indent, id));
+    if let Some(label) = &subgraph.label {
+        out.push_str(&format!(" [\"{}\"]", escape(label)));
+    }
+    out.push('\n');
+
+    for node in &subgraph.nodes {
+        if let Some(node) = graph.node(node) {
+            push_node(out, node, ids, depth + 1);
+        }
+    }
+
+    for child in &graph.subgraphs {
+        if child.parent.as_deref() == Some(subgraph.id.as_str()) {
+            push_subgraph(out, graph, child, ids, depth + 1);
+        }
+    }
+
+    out.push_str(&indent);
+    out.push_str("end\n");
+}
+
+fn link(edge: &GraphEdge, directed: bool) -> String {
+    let attribute = |key: &str| {
+        edge.attributes
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let head = directed && attribute("arrowhead") != Some("none");
+    let both = head && attribute("dir") == Some("both");
+
+    let body = match (attribute("style"), head) {
+        (Some("invis"), _) => "~~~",
+        (Some("dotted"), true) => "-.->",
+        (Some("dotted"), false) => "-.-",
+        (Some("bold"), true) => "==>",
+        (Some("bold"), false) => "===",
+        (_, true) => "-->",
+        (_, false) => "---",
+    };
+
+    if both {
+        format!("<{}", body)
+    } else {
+        body.to_owned()
+    }
+}
+
+fn is_identifier(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(is_id_char) && !RESERVED.contains(&id)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("#quot;", "\"")
+        .replace("<br/>", "\n")
+        .replace("<br>", "\n")
+}
+
+/// Splits a line on `;` outside of quotes, dropping `%%` comments and
+/// returning the 1-based column where every statement starts.
+fn statements(line: &str) -> Vec<(usize, String)> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start = 1;
+    let mut quoted = false;
+    let chars: Vec<char> = line.chars().collect();
+
+    for (index, c) in chars.iter().enumerate() {
+        match c {
+            '"' => quoted = !quoted,
+            '%' if !quoted && chars.get(index + 1) == Some(&'%') => break,
+            ';' if !quoted => {
+                statements.push((start, std::mem::take(&mut current)));
+                start = index + 2;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(*c);
+    }
+
+    statements.push((start, current));
+    statements
+}
+
+struct Cursor {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(offset, c)| self.peek_at(offset) == Some(c))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.index;
+        while self.peek().is_some_and(is_id_char) {
+            self.index += 1;
+        }
+        self.chars[start..self.index].iter().collect()
+    }
+
+    fn rest(&mut self) -> String {
+        let rest = self.chars[self.index..].iter().collect::<String>();
+        self.index = self.chars.len();
+        rest.trim().to_owned()
+    }
+
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        self.index += 1;
+        let start = self.index;
+        while self.peek().is_some_and(|c| c != '"') {
+            self.index += 1;
+        }
+
+        if self.peek().is_none() {
+            return Err(self.error("unterminated string"));
+        }
+
+        let text = self.chars[start..self.index].iter().collect();
+        self.index += 1;
+        Ok(text)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column + self.index, message)
+    }
+}
+
+struct Parser {
+    graph: Graph,
+    stack: Vec<String>,
+    header: bool,
+}
+
+impl Parser {
+    fn statement(&mut self, cursor: &mut Cursor) -> Result<(), ParseError> {
+        cursor.skip_whitespace();
+
+        if !self.header {
+            let start = cursor.index;
+            let keyword = cursor.word();
+            if keyword != "flowchart" && keyword != "graph" {
+                cursor.index = start;
+                return Err(cursor.error("expected a `flowchart` or `graph` header"));
+            }
+
+            cursor.rest();
+            self.header = true;
+            return Ok(());
+        }
+
+        let start = cursor.index;
+        let keyword = cursor.word();
+        cursor.skip_whitespace();
+
+        match keyword.as_str() {
+            "subgraph" => self.subgraph(cursor),
+            "end" if cursor.peek().is_none() => {
+                if self.stack.pop().is_none() {
+                    cursor.index = start;
+                    return Err(cursor.error("`end` without a matching `subgraph`"));
+                }
+                Ok(())
+            }
+            "direction" | "classDef" | "class" | "style" | "linkStyle" | "click" => {
+                cursor.rest();
+                Ok(())
+            }
+            _ => {
+                cursor.index = start;
+                self.chain(cursor)
+            }
+        }
+    }
+
+    fn subgraph(&mut self, cursor: &mut Cursor) -> Result<(), ParseError> {
+        let (id, label) = if cursor.peek() == Some('"') {
+            let label = unescape(&cursor.quoted()?);
+            (label.clone(), Some(label))
+        } else {
+            let id = cursor.word();
+            if id.is_empty() {
+                return Err(cursor.error("expected a subgraph id"));
+            }
+
+            cursor.skip_whitespace();
+            if cursor.peek() == Some('[') {
+                cursor.index += 1;
+                let label = self.text(cursor, "]")?;
+                (id, Some(label))
+            } else if cursor.peek().is_some() {
+                let title = format!("{} {}", id, cursor.rest());
+                (title.clone(), Some(title))
+            } else {
+                (id, None)
+            }
+        };
+
+        self.graph.subgraphs.push(Subgraph {
+            id: id.clone(),
+            label,
+            parent: self.stack.last().cloned(),
+            nodes: Vec::new(),
+        });
+        self.stack.push(id);
+
+        Ok(())
+    }
+
+    fn chain(&mut self, cursor: &mut Cursor) -> Result<(), ParseError> {
+        let mut previous = self.group(cursor)?;
+
+        loop {
+            cursor.skip_whitespace();
+            if cursor.peek().is_none() {
+                return Ok(());
+            }
+
+            let (attributes, label) = self.link(cursor)?;
+            cursor.skip_whitespace();
+            let next = self.group(cursor)?;
+
+            for from in &previous {
+                for to in &next {
+                    let mut edge = GraphEdge::new(from.clone(), to.clone());
+                    edge.label = label.clone();
+                    edge.attributes = attributes.clone();
+                    self.graph.edges.push(edge);
+                }
+            }
+
+            previous = next;
+        }
+    }
+
+    fn group(&mut self, cursor: &mut Cursor) -> Result<Vec<String>, ParseError> {
+        let mut nodes = vec![self.node(cursor)?];
+
+        loop {
+            let checkpoint = cursor.index;
+            cursor.skip_whitespace();
+
+            if cursor.peek() == Some('&') {
+                cursor.index += 1;
+                cursor.skip_whitespace();
+                nodes.push(self.node(cursor)?);
+            } else {
+                cursor.index = checkpoint;
+                return Ok(nodes);
+            }
+        }
+    }
+
+    fn node(&mut self, cursor: &mut Cursor) -> Result<String, ParseError> {
+        let id = cursor.word();
+        if id.is_empty() {
+            return Err(cursor.error("expected a node id"));
+        }
+
+        let shape = SHAPES
+            .iter()
+            .find(|(_, opening, _)| cursor.starts_with(opening))
+            .map(|(_, opening, _)| *opening);
+
+        let label = match shape {
+            Some(opening) => {
+                cursor.index += opening.chars().count();
+                Some(self.shape(cursor, opening)?)
+            }
+            None => None,
+        };
+
+        if cursor.starts_with(":::") {
+            cursor.index += 3;
+            cursor.word();
+        }
+
+        self.graph.ensure_node(&id);
+
+        if let Some(current) = self.stack.last() {
+            for subgraph in &mut self.graph.subgraphs {
+                subgraph.nodes.retain(|node| *node != id);
+            }
+            if let Some(subgraph) = self.graph.subgraphs.iter_mut().find(|s| s.id == *current) {
+                subgraph.nodes.push(id.clone());
+            }
+        }
+
+        if let Some((shape, label)) = label {
+            let node = self.graph.ensure_node(&id);
+            node.label = Some(label);
+            node.attributes.retain(|(key, _)| key != "shape");
+            if shape != DEFAULT_SHAPE.0 {
+                node.attributes.push(("shape".to_owned(), shape.to_owned()));
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Reads a node label up to whichever closing delimiter matches `opening`.
+    fn shape(
+        &mut self,
+        cursor: &mut Cursor,
+        opening: &str,
+    ) -> Result<(&'static str, String), ParseError> {
+        let candidates: Vec<_> = SHAPES
+            .iter()
+            .filter(|(_, other, _)| *other == opening)
+            .collect();
+        let closing_at = |cursor: &Cursor| {
+            candidates
+                .iter()
+                .find(|(_, _, closing)| cursor.starts_with(closing))
+        };
+
+        cursor.skip_whitespace();
+        let label = if cursor.peek() == Some('"') {
+            let label = cursor.quoted()?;
+            cursor.skip_whitespace();
+            label
+        } else {
+            let start = cursor.index;
+            while cursor.peek().is_some() && closing_at(cursor).is_none() {
+                cursor.index += 1;
+            }
+            cursor.chars[start..cursor.index]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_owned()
+        };
+
+        match closing_at(cursor) {
+            Some((name, _, closing)) => {
+                cursor.index += closing.chars().count();
+                Ok((name, unescape(&label)))
+            }
+            None => Err(cursor.error("unclosed node shape")),
+        }
+    }
+
+    fn text(&mut self, cursor: &mut Cursor, closing: &str) -> Result<String, ParseError> {
+        cursor.skip_whitespace();
+        let text = if cursor.peek() == Some('"') {
+            let text = cursor.quoted()?;
+            cursor.skip_whitespace();
+            text
+        } else {
+            let start = cursor.index;
+            while cursor.peek().is_some() && !cursor.starts_with(closing) {
+                cursor.index += 1;
+            }
+            cursor.chars[start..cursor.index]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_owned()
+        };
+
+        if !cursor.starts_with(closing) {
+            return Err(cursor.error(format!("expected `{}`", closing)));
+        }
+        cursor.index += closing.chars().count();
+
+        Ok(unescape(&text))
+    }
+
+    /// Reads a link such as `-->`, `-.->`, `==>`, `---` or `-- text -->`,
+    /// followed by an optional `|text|`.
+    fn link(&mut self, cursor: &mut Cursor) -> Result<(Attributes, Option<String>), ParseError> {
+        let start = cursor.index;
+        let mut both = false;
+
+        if cursor.peek() == Some('<') {
+            both = true;
+            cursor.index += 1;
+        } else if matches!(cursor.peek(), Some('o' | 'x'))
+            && matches!(cursor.peek_at(1), Some('-' | '='))
+        {
+            cursor.index += 1;
+        }
+
+        let mut body = self.link_body(cursor);
+        if body.chars().count() < 2 {
+            cursor.index = start;
+            return Err(cursor.error("expected a link"));
+        }
+
+        let mut label = None;
+
+        if matches!(body.as_str(), "--" | "==" | "-.") && cursor.peek() == Some(' ') {
+            let text_start = cursor.index;
+            loop {
+                match cursor.peek() {
+                    None => {
+                        cursor.index = start;
+                        return Err(cursor.error("unterminated link text"));
+                    }
+                    Some(c)
+                        if c.is_whitespace()
+                            && matches!(cursor.peek_at(1), Some('-' | '=' | '.')) =>
+                    {
+                        let text: String = cursor.chars[text_start..cursor.index].iter().collect();
+                        cursor.index += 1;
+                        let checkpoint = cursor.index;
+                        let closing = self.link_body(cursor);
+                        if closing.chars().count() >= 2 {
+                            let text = text.trim();
+                            let text = text
+                                .strip_prefix('"')
+                                .and_then(|text| text.strip_suffix('"'))
+                                .unwrap_or(text);
+                            label = Some(unescape(text));
+                            body.push_str(&closing);
+                            break;
+                        }
+                        cursor.index = checkpoint;
+                    }
+                    Some(_) => cursor.index += 1,
+                }
+            }
+        }
+
+        let head = match cursor.peek() {
+            Some('>') => {
+                cursor.index += 1;
+                true
+            }
+            Some('o' | 'x')
+                if matches!(cursor.peek_at(1), None | Some('|'))
+                    || cursor.peek_at(1).is_some_and(char::is_whitespace) =>
+            {
+                cursor.index += 1;
+                true
+            }
+            _ => false,
+        };
+
+        cursor.skip_whitespace();
+        if cursor.peek() == Some('|') {
+            cursor.index += 1;
+            label = Some(self.text(cursor, "|")?);
+        }
+
+        let mut attributes = Vec::new();
+        if body.contains('~') {
+            attributes.push(("style".to_owned(), "invis".to_owned()));
+        } else if body.contains('=') {
+            attributes.push(("style".to_owned(), "bold".to_owned()));
+        } else if body.contains('.') {
+            attributes.push(("style".to_owned(), "dotted".to_owned()));
+        }
+        if !head {
+            attributes.push(("arrowhead".to_owned(), "none".to_owned()));
+        } else if both {
+            attributes.push(("dir".to_owned(), "both".to_owned()));
+        }
+
+        Ok((attributes, label))
+    }
+
+    fn link_body(&mut self, cursor: &mut Cursor) -> String {
+        let start = cursor.index;
+        while matches!(cursor.peek(), Some('-' | '=' | '.' | '~')) {
+            cursor.index += 1;
+        }
+        cursor.chars[start..cursor.index].iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Graph {
+        let mut graph = Graph::new();
+
+        let mut source = GraphNode::new("source");
+        source.label = Some("Read \"input\"\nfrom disk".to_owned());
+        source.attributes = vec![("shape".to_owned(), "stadium".to_owned())];
+        graph.nodes.push(source);
+
+        let mut check = GraphNode::new("check");
+        check.label = Some("Valid?".to_owned());
+        check.attributes = vec![("shape".to_owned(), "rhombus".to_owned())];
+        graph.nodes.push(check);
+
+        graph.nodes.push(GraphNode::new("sink"));
+        graph.nodes.push(GraphNode::new("end"));
+
+        graph.subgraphs.push(Subgraph {
+            id: "io".to_owned(),
+            label: Some("Input & output".to_owned()),
+            parent: None,
+            nodes: vec!["source".to_owned(), "sink".to_owned()],
+        });
+
+        let mut edge = GraphEdge::new("source", "check");
+        edge.label = Some("two\nlines".to_owned());
+        graph.edges.push(edge);
+
+        let mut edge = GraphEdge::new("check", "sink");
+        edge.attributes = vec![("style".to_owned(), "dotted".to_owned())];
+        graph.edges.push(edge);
+
+        let mut edge = GraphEdge::new("check", "end");
+        edge.attributes = vec![("arrowhead".to_owned(), "none".to_owned())];
+        graph.edges.push(edge);
+
+        graph
+    }
+
+    #[test]
+    fn export_then_parse_round_trips() {
+        let graph = sample();
+        let text = export(&graph);
+        let parsed = parse(&text).unwrap();
+
+        let nodes = |graph: &Graph| {
+            let mut nodes: Vec<_> = graph
+                .nodes
+                .iter()
+                .map(|node| {
+                    let label = node.label.clone().unwrap_or_else(|| node.id.clone());
+                    (label, node.attributes.clone())
+                })
+                .collect();
+            nodes.sort();
+            nodes
+        };
+        assert_eq!(nodes(&parsed), nodes(&graph));
+
+        let labels: Vec<_> = parsed.edges.iter().map(|e| e.label.clone()).collect();
+        assert_eq!(labels, vec![Some("two\nlines".to_owned()), None, None]);
+        let attributes: Vec<_> = parsed.edges.iter().map(|e| e.attributes.clone()).collect();
+        let expected: Vec<_> = graph.edges.iter().map(|e| e.attributes.clone()).collect();
+        assert_eq!(attributes, expected);
+
+        assert_eq!(parsed.subgraphs.len(), 1);
+        assert_eq!(parsed.subgraphs[0].label.as_deref(), Some("Input & output"));
+        assert_eq!(parsed.subgraphs[0].nodes.len(), 2);
+    }
+
+    #[test]
+    fn export_keeps_labels_on_one_line() {
+        let text = export(&sample());
+
+        assert!(text.contains("source([\"Read #quot;input#quot;<br>from disk\"])"));
+        assert!(text.contains("|\"two<br>lines\"|"));
+        assert!(!text.contains("end[") && !text.contains("\n    end\n    check"));
+    }
+
+    #[test]
+    fn parses_chains_groups_and_link_text() {
+        let graph = parse(
+            "```mermaid\nflowchart TD\n  a & b --> c[(Store)] -. save .-> d{{Hex}}; d o--o e\n  %% comment\n  c -- \"label\" --> e\n```",
+        )
+        .unwrap();
+
+        let ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c", "d", "e"]);
+        assert_eq!(graph.node("c").unwrap().label.as_deref(), Some("Store"));
+        assert_eq!(
+            graph.node("d").unwrap().attributes,
+            vec![("shape".to_owned(), "hexagon".to_owned())]
+        );
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.label.as_deref()))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("a", "c", None),
+                ("b", "c", None),
+                ("c", "d", Some("save")),
+                ("d", "e", None),
+                ("c", "e", Some("label")),
+            ]
+        );
+        assert_eq!(
+            graph.edges[2].attributes,
+            vec![("style".to_owned(), "dotted".to_owned())]
+        );
+    }
+
+    #[test]
+    fn parse_and_arrange_positions_every_node() {
+        let graph = parse_and_arrange(
+            "graph LR\n  a --> b --> c\n  a --> c",
+            |_| Size::new(100.0, 40.0),
+            &LayeredOptions::default(),
+        )
+        .unwrap();
+
+        let x = |id: &str| graph.node(id).unwrap().position.unwrap().x;
+        assert!(x("a") < x("b") && x("b") < x("c"));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        let error = parse("a --> b").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+
+        let error = parse("flowchart LR\n  a[Open --> b").unwrap_err();
+        assert_eq!(error.line, 2);
+
+        let error = parse("flowchart LR\n  subgraph s\n  a").unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let error = parse("flowchart LR\nend").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+}
@@ -1,30 +1,53 @@
 use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
-use iced::{Point, Size};
+use iced::advanced::graphics::Mesh;
+use iced::advanced::Renderer;
+use iced::{Point, Size, Vector};
 
 pub trait MeshRenderer {
     fn draw_buffers(&mut self, buffers: Indexed<SolidVertex2D>);
 }
 
 impl<Theme> MeshRenderer for iced::Renderer<Theme> {
-    fn draw_buffers(&mut self, buffers: Indexed<SolidVertex2D>) {
-        let min = buffers
-            .vertices
-            .iter()
-            .fold(Point::new(f32::MAX, f32::MAX), |min, v| {
-                Point::new(min.x.min(v.position[0]), min.y.min(v.position[1]))
-            });
+    fn draw_buffers(&mut self, mut buffers: Indexed<SolidVertex2D>) {
+        let Some((origin, size)) = fit(buffers.vertices.iter_mut().map(|v| &mut v.position)) else {
+            return;
+        };
 
-        let max = buffers
-            .vertices
-            .iter()
-            .fold(Point::new(f32::MIN, f32::MIN), |max, v| {
-                Point::new(max.x.max(v.position[0]), max.y.max(v.position[1]))
-            });
+        self.with_translation(origin, |renderer| {
+            renderer.draw_mesh(Mesh::Solid { buffers, size });
+        });
+    }
+}
 
-        let size = Size::new(max.x - min.x, max.y - min.y);
+/// Moves the positions so that their bounding box starts at the origin, since meshes
+/// are clipped to `(0, 0)..size`, and returns the offset to draw them at along with
+/// a size of at least one pixel on each axis, so that straight lines are kept.
+fn fit<'a>(positions: impl Iterator<Item = &'a mut [f32; 2]>) -> Option<(Vector, Size)> {
+    let mut positions: Vec<&mut [f32; 2]> = positions.collect();
+    if positions.is_empty() {
+        return None;
+    }
 
-        if size.width >= 1.0 && size.height >= 1.0 {
-            self.draw_mesh(iced::advanced::graphics::Mesh::Solid { buffers, size });
-        }
+    let (min, max) = positions.iter().fold(
+        (
+            Point::new(f32::MAX, f32::MAX),
+            Point::new(f32::MIN, f32::MIN),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p[0]), min.y.min(p[1])),
+                Point::new(max.x.max(p[0]), max.y.max(p[1])),
+            )
+        },
+    );
+
+    for position in &mut positions {
+        position[0] -= min.x;
+        position[1] -= min.y;
     }
+
+    Some((
+        Vector::new(min.x, min.y),
+        Size::new((max.x - min.x).max(1.0), (max.y - min.y).max(1.0)),
+    ))
 }
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use iced::{Point, Size};
+
+use crate::layout::{layered, LayeredOptions};
+
+/// A plain description of a graph, used to exchange graphs with other tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    pub name: Option<String>,
+    pub directed: bool,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub subgraphs: Vec<Subgraph>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: Option<String>,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    /// Top-left corner in graph space, as given to [`Node::position`](crate::Node::position).
+    pub position: Option<Point>,
+    /// Attributes without a dedicated field, kept so they survive a round trip.
+    pub attributes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    pub from: String,
+    pub from_port: Option<String>,
+    pub to: String,
+    pub to_port: Option<String>,
+    pub label: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+/// A named group of nodes, possibly nested inside another group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subgraph {
+    pub id: String,
+    pub label: Option<String>,
+    pub parent: Option<String>,
+    pub nodes: Vec<String>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Graph {
+            name: None,
+            directed: true,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            subgraphs: Vec::new(),
+        }
+    }
+
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    pub fn node_mut(&mut self, id: &str) -> Option<&mut GraphNode> {
+        self.nodes.iter_mut().find(|node| node.id == id)
+    }
+
+    /// Returns the node with the given id, appending it first if missing.
+    pub fn ensure_node(&mut self, id: &str) -> &mut GraphNode {
+        match self.nodes.iter().position(|node| node.id == id) {
+            Some(index) => &mut self.nodes[index],
+            None => {
+                self.nodes.push(GraphNode::new(id));
+                self.nodes.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Positions every node with [`layered`], using `size` to get the size
+    /// each node will have once shown in a [`GraphContainer`](crate::GraphContainer).
+    pub fn arrange(&mut self, size: impl Fn(&GraphNode) -> Size, options: &LayeredOptions) {
+        let nodes: Vec<(String, Size)> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.clone(), size(node)))
+            .collect();
+        let edges: Vec<(String, String)> = self
+            .edges
+            .iter()
+            .map(|edge| (edge.from.clone(), edge.to.clone()))
+            .collect();
+
+        let positions = layered(&nodes, &edges, options);
+        for node in &mut self.nodes {
+            node.position = positions.get(&node.id).copied();
+        }
+    }
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphNode {
+    pub fn new(id: impl Into<String>) -> Self {
+        GraphNode {
+            id: id.into(),
+            label: None,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            position: None,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+impl GraphEdge {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        GraphEdge {
+            from: from.into(),
+            from_port: None,
+            to: to.into(),
+            to_port: None,
+            label: None,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+/// An error raised while reading a graph from text, pointing at the 1-based
+/// line and column where parsing stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,3 +1,7 @@
+use std::ops::Range;
+use std::sync::Mutex;
+
+use iced::advanced::widget::tree;
 use iced::advanced::{renderer, widget, Clipboard, Layout, Shell, Widget};
 use iced::{
     alignment, event, mouse, Alignment, Background, Color, Element, Event, Length, Padding, Point,
@@ -5,11 +9,88 @@ use iced::{
 };
 
 use crate::{
-    node_element::{GraphNodeElement, ScalableWidget},
-    styles::node::StyleSheet,
+    node_element::{GraphNodeElement, LayoutContext, ScalableWidget},
+    port::{Endpoint, Port, PortAnchors},
+    styles::node::{Appearance, StyleSheet},
+    tooltip::{self, TooltipStyle},
 };
 
-pub struct Node<'a, Message, Renderer>
+/// Space around the contents of the title bar and of the footer.
+const BAR_PADDING: f32 = 6.0;
+/// Space between the icon and the title.
+const HEADER_SPACING: f32 = 6.0;
+const PORT_RADIUS: f32 = 5.0;
+/// Vertical space between ports, and above and below them.
+const PORT_SPACING: f32 = 6.0;
+/// Width and height of the collapse toggle.
+const TOGGLE_SIZE: f32 = 12.0;
+/// Distance from the node edge to the port labels.
+const PORT_INSET: f32 = 12.0;
+/// Space between the port labels and the content.
+const PORT_GAP: f32 = 10.0;
+/// Largest number of layers a shadow or a glow is drawn with.
+const SHADOW_LAYERS: usize = 8;
+/// Width in pixels of the area around the right and bottom edges where the node is resized.
+const HANDLE_WIDTH: f32 = 8.0;
+/// Radius of the status badge on the top right corner.
+const BADGE_RADIUS: f32 = 6.0;
+/// Opacity of muted and bypassed nodes.
+const MUTED_ALPHA: f32 = 0.45;
+/// Width of the wire drawn across bypassed nodes.
+const BYPASS_WIDTH: f32 = 2.0;
+
+/// Where a [`Node`] can be grabbed to drag it around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DragRegion {
+    /// Anywhere on the node that doesn't handle the press itself.
+    #[default]
+    Node,
+    /// The title bar, or the whole node if it has none.
+    Header,
+    /// The element set with [`Node::drag_handle`].
+    Handle,
+}
+
+/// State of the work a [`Node`] stands for, shown as a badge on its corner and as
+/// the color of its border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Status {
+    #[default]
+    Idle,
+    Running,
+    Success,
+    Warning,
+    Error,
+}
+
+/// How a [`Node`] takes part in evaluating the graph, which is up to the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Active,
+    /// The node is skipped and its outputs carry nothing. Drawn faded.
+    Muted,
+    /// The node is skipped and its first input is passed on to its first output. Drawn
+    /// faded, with a wire between the two ports.
+    Bypassed,
+}
+
+impl Mode {
+    /// Whether the node computes its outputs.
+    pub fn is_evaluated(self) -> bool {
+        self == Mode::Active
+    }
+
+    /// The input whose value the given output carries without the node being evaluated.
+    pub fn forwarded_input(self, output: usize) -> Option<usize> {
+        (self == Mode::Bypassed && output == 0).then_some(0)
+    }
+}
+
+type CollapseHandler<'a, Message> = Box<dyn Fn(bool) -> Message + 'a>;
+type ResizeHandler<'a, Message> = Box<dyn Fn(Size) -> Message + 'a>;
+
+pub struct Node<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -24,14 +105,77 @@ where
     position: Point,
     horizontal_alignment: alignment::Horizontal,
     vertical_alignment: alignment::Vertical,
-    on_translate: Option<Box<dyn Fn((f32, f32)) -> Message + 'a>>,
+    id: Option<Id>,
+    selected: bool,
+    z_index: i32,
+    drag_region: DragRegion,
+    drag_handle: Option<Element<'a, Message, Renderer>>,
+    title: Option<Element<'a, Message, Renderer>>,
+    icon: Option<Element<'a, Message, Renderer>>,
+    inputs: Vec<Port<'a, Message, Renderer>>,
+    outputs: Vec<Port<'a, Message, Renderer>>,
+    footer: Option<Element<'a, Message, Renderer>>,
+    collapsed: bool,
+    on_collapse: Option<CollapseHandler<'a, Message>>,
+    on_resize: Option<ResizeHandler<'a, Message>>,
+    status: Status,
+    mode: Mode,
+    status_message: Option<Element<'a, Message, Renderer>>,
+    tooltip: Option<Element<'a, Message, Renderer>>,
+
+    measurements: Mutex<Measurements>,
 }
 
-struct NodeState {
-    drag_start_position: Option<Point>,
+/// Sizes computed during layout, which resizing depends on.
+#[derive(Debug, Clone)]
+struct Measurements {
+    scale: f32,
+    /// Smallest size in graph units that still fits the contents.
+    min_size: Size,
+    /// Whether each of the inputs, then each of the outputs, has a connection.
+    connected: Vec<bool>,
+}
+
+#[derive(Default)]
+struct State {
+    resize: Option<Resize>,
+    hovered: bool,
+}
+
+struct Resize {
+    handle: Handle,
+    origin: Point,
+    /// Size in graph units when the resize started.
+    size: Size,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handle {
+    Right,
+    Bottom,
+    Corner,
+}
+
+impl Handle {
+    fn resizes_width(self) -> bool {
+        self != Handle::Bottom
+    }
+
+    fn resizes_height(self) -> bool {
+        self != Handle::Right
+    }
+
+    fn interaction(self) -> mouse::Interaction {
+        match self {
+            Handle::Right => mouse::Interaction::ResizingHorizontally,
+            Handle::Bottom => mouse::Interaction::ResizingVertically,
+            // There is no diagonal resize cursor.
+            Handle::Corner => mouse::Interaction::Crosshair,
+        }
+    }
 }
 
-impl<'a, Message, Renderer> Node<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> Node<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -51,15 +195,158 @@ where
             position: Point::new(0.0, 0.0),
             horizontal_alignment: alignment::Horizontal::Left,
             vertical_alignment: alignment::Vertical::Top,
-            on_translate: None,
+            id: None,
+            selected: false,
+            z_index: 0,
+            drag_region: DragRegion::default(),
+            drag_handle: None,
+            title: None,
+            icon: None,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            footer: None,
+            collapsed: false,
+            on_collapse: None,
+            on_resize: None,
+            status: Status::default(),
+            mode: Mode::default(),
+            status_message: None,
+            tooltip: None,
+            measurements: Mutex::new(Measurements {
+                scale: 1.0,
+                min_size: Size::ZERO,
+                connected: Vec::new(),
+            }),
         }
     }
 
-    pub fn on_translate<F>(mut self, f: F) -> Self
+    pub fn id(mut self, id: Id) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Nodes with a higher z-index are drawn above the others and get events first.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    pub fn drag_region(mut self, drag_region: DragRegion) -> Self {
+        self.drag_region = drag_region;
+        self
+    }
+
+    /// Shows the given element at the end of the title bar, and only lets the node be
+    /// dragged by it.
+    pub fn drag_handle<T>(mut self, handle: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.drag_handle = Some(handle.into());
+        self.drag_region = DragRegion::Handle;
+        self
+    }
+
+    /// Shows a title bar with the given title.
+    pub fn title<T>(mut self, title: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Shows an icon in the title bar, before the title.
+    pub fn icon<T>(mut self, icon: T) -> Self
     where
-        F: 'a + Fn((f32, f32)) -> Message,
+        T: Into<Element<'a, Message, Renderer>>,
     {
-        self.on_translate = Some(Box::new(f));
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Adds a port on the left side of the body.
+    pub fn input(mut self, port: Port<'a, Message, Renderer>) -> Self {
+        self.inputs.push(port);
+        self
+    }
+
+    /// Adds a port on the right side of the body.
+    pub fn output(mut self, port: Port<'a, Message, Renderer>) -> Self {
+        self.outputs.push(port);
+        self
+    }
+
+    pub fn footer<T>(mut self, footer: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Shrinks the node to its title bar, with all its ports on the ends of the bar.
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    /// Shows a toggle in the title bar, which publishes the message built from the
+    /// new collapsed state when clicked.
+    pub fn on_collapse<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(bool) -> Message,
+    {
+        self.on_collapse = Some(Box::new(f));
+        self
+    }
+
+    /// Makes the node resizable by dragging its right and bottom edges, publishing the
+    /// message built from the new size in graph units, no smaller than the contents.
+    ///
+    /// The node keeps the size given by [`width`](Self::width) and [`height`](Self::height),
+    /// so the application has to store the new size and pass it back.
+    pub fn on_resize<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Size) -> Message,
+    {
+        self.on_resize = Some(Box::new(f));
+        self
+    }
+
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Fades the node out unless it is active, and draws a wire across it while bypassed.
+    /// Users toggle it with [`GraphEvent::ModesRequested`](crate::GraphEvent::ModesRequested).
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Shows the given element in a tooltip while the status badge is hovered.
+    pub fn status_message<T>(mut self, message: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.status_message = Some(message.into());
+        self
+    }
+
+    /// Shows the given element in a tooltip while the node is hovered, after the
+    /// delay of the [`GraphContainer`](crate::GraphContainer).
+    pub fn tooltip<T>(mut self, tooltip: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.tooltip = Some(tooltip.into());
         self
     }
 
@@ -119,9 +406,654 @@ where
     }
 }
 
-pub fn node<'a, Message, Renderer>(
+impl<'a, Id, Message, Renderer> Node<'a, Id, Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    fn is_structured(&self) -> bool {
+        self.has_header()
+            || self.footer.is_some()
+            || !self.inputs.is_empty()
+            || !self.outputs.is_empty()
+    }
+
+    fn has_header(&self) -> bool {
+        self.title.is_some()
+            || self.icon.is_some()
+            || self.drag_handle.is_some()
+            || self.on_collapse.is_some()
+    }
+
+    /// Only nodes with a title bar can be collapsed.
+    fn is_collapsed(&self) -> bool {
+        self.collapsed && self.has_header()
+    }
+
+    /// Indices of the icon, the title and the drag handle among the elements.
+    fn header_elements(&self) -> Range<usize> {
+        1..1 + self.icon.iter().count()
+            + self.title.iter().count()
+            + self.drag_handle.iter().count()
+    }
+
+    /// What each of the elements is, in the same order.
+    fn parts(&self) -> Vec<Part> {
+        let header = self.header_elements().map(|_| Part::Header);
+        let ports = self
+            .inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .enumerate()
+            .flat_map(|(i, port)| {
+                std::iter::once(Part::Label(i)).chain(port.editor.as_ref().map(|_| Part::Editor(i)))
+            });
+
+        std::iter::once(Part::Content)
+            .chain(header)
+            .chain(ports)
+            .chain(self.footer.iter().map(|_| Part::Footer))
+            .chain(self.status_message.iter().map(|_| Part::StatusMessage))
+            .chain(self.tooltip.iter().map(|_| Part::Tooltip))
+            .chain(
+                self.inputs
+                    .iter()
+                    .chain(self.outputs.iter())
+                    .enumerate()
+                    .filter(|(_, port)| port.tooltip.is_some())
+                    .map(|(i, _)| Part::PortTooltip(i)),
+            )
+            .collect()
+    }
+
+    /// Whether each of the elements is shown: only the title bar while collapsed, and
+    /// either the editor or the label of each port depending on whether it is connected.
+    /// Tooltips are drawn apart.
+    fn visibility(&self) -> Vec<bool> {
+        let collapsed = self.is_collapsed();
+        let connected = self
+            .measurements
+            .lock()
+            .expect("Could not lock mutex")
+            .connected
+            .clone();
+        let connected = |port: usize| connected.get(port).copied().unwrap_or(false);
+        let ports: Vec<_> = self.inputs.iter().chain(self.outputs.iter()).collect();
+
+        self.parts()
+            .into_iter()
+            .map(|part| match part {
+                Part::StatusMessage | Part::Tooltip | Part::PortTooltip(_) => false,
+                Part::Header => true,
+                _ if collapsed => false,
+                Part::Label(port) => connected(port) || ports[port].editor.is_none(),
+                Part::Editor(port) => !connected(port),
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Layouts of the title bar, the collapse toggle and the port dots, in this order.
+    fn chrome<'b>(&self, layout: Layout<'b>) -> impl Iterator<Item = Layout<'b>> {
+        layout.children().skip(if self.is_structured() {
+            self.elements().len()
+        } else {
+            usize::MAX
+        })
+    }
+
+    /// The resize handle at `position`, given in the same space as `bounds`.
+    fn handle_at(&self, bounds: Rectangle, position: Point) -> Option<Handle> {
+        self.on_resize.as_ref()?;
+
+        let reach = HANDLE_WIDTH / 2.0;
+        let right = (position.x - (bounds.x + bounds.width)).abs() <= reach
+            && position.y >= bounds.y
+            && position.y <= bounds.y + bounds.height + reach;
+        let bottom = !self.is_collapsed()
+            && (position.y - (bounds.y + bounds.height)).abs() <= reach
+            && position.x >= bounds.x
+            && position.x <= bounds.x + bounds.width + reach;
+
+        match (right, bottom) {
+            (true, true) => Some(Handle::Corner),
+            (true, false) => Some(Handle::Right),
+            (false, true) => Some(Handle::Bottom),
+            (false, false) => None,
+        }
+    }
+
+    fn toggle_bounds(&self, layout: Layout<'_>) -> Option<Rectangle> {
+        self.on_collapse.as_ref()?;
+        self.chrome(layout).nth(1).map(|toggle| toggle.bounds())
+    }
+
+    fn tooltip_count(&self) -> usize {
+        self.status_message.iter().count()
+            + self.tooltip.iter().count()
+            + self
+                .inputs
+                .iter()
+                .chain(self.outputs.iter())
+                .filter(|port| port.tooltip.is_some())
+                .count()
+    }
+
+    /// The port whose dot, label or editor is at `position`.
+    fn port_at(&self, layout: Layout<'_>, position: Point) -> Option<usize> {
+        let dot = self
+            .chrome(layout)
+            .skip(2)
+            .position(|dot| dot.bounds().contains(position));
+
+        dot.or_else(|| {
+            self.parts()
+                .into_iter()
+                .zip(layout.children())
+                .zip(self.visibility())
+                .find_map(|((part, layout), visible)| match part {
+                    Part::Label(port) | Part::Editor(port)
+                        if visible && layout.bounds().contains(position) =>
+                    {
+                        Some(port)
+                    }
+                    _ => None,
+                })
+        })
+    }
+
+    /// The status badge, centered on the top right corner of `bounds`.
+    fn badge_bounds(&self, bounds: Rectangle) -> Rectangle {
+        let scale = self
+            .measurements
+            .lock()
+            .expect("Could not lock mutex")
+            .scale;
+        let radius = BADGE_RADIUS * scale;
+
+        Rectangle {
+            x: bounds.x + bounds.width - radius,
+            y: bounds.y - radius,
+            width: radius * 2.0,
+            height: radius * 2.0,
+        }
+    }
+
+    /// The child elements, in the order of their layouts and trees.
+    fn elements(&self) -> Vec<&Element<'a, Message, Renderer>> {
+        std::iter::once(&self.content)
+            .chain(self.icon.iter())
+            .chain(self.title.iter())
+            .chain(self.drag_handle.iter())
+            .chain(
+                self.inputs
+                    .iter()
+                    .chain(self.outputs.iter())
+                    .flat_map(|port| std::iter::once(&port.label).chain(port.editor.iter())),
+            )
+            .chain(self.footer.iter())
+            .chain(self.status_message.iter())
+            .chain(self.tooltip.iter())
+            .chain(
+                self.inputs
+                    .iter()
+                    .chain(self.outputs.iter())
+                    .filter_map(|port| port.tooltip.as_ref()),
+            )
+            .collect()
+    }
+
+    fn elements_mut(&mut self) -> Vec<&mut Element<'a, Message, Renderer>> {
+        let mut ports = Vec::new();
+        let mut port_tooltips = Vec::new();
+
+        for port in self.inputs.iter_mut().chain(self.outputs.iter_mut()) {
+            ports.push(&mut port.label);
+            ports.extend(port.editor.as_mut());
+            port_tooltips.extend(port.tooltip.as_mut());
+        }
+
+        std::iter::once(&mut self.content)
+            .chain(self.icon.iter_mut())
+            .chain(self.title.iter_mut())
+            .chain(self.drag_handle.iter_mut())
+            .chain(ports)
+            .chain(self.footer.iter_mut())
+            .chain(self.status_message.iter_mut())
+            .chain(self.tooltip.iter_mut())
+            .chain(port_tooltips)
+            .collect()
+    }
+
+    /// Lays out the title bar, the ports on both sides of the content and the footer.
+    ///
+    /// The node is laid out on screen, where the elements keep their size at any zoom,
+    /// so only the spacing around them and the limits follow `scale`. The returned
+    /// minimum size is in graph units.
+    ///
+    /// After the layouts of the elements come the title bar, the collapse toggle and the
+    /// port dots, which are drawn by the node itself.
+    fn layout_structured(
+        &self,
+        renderer: &Renderer,
+        limits: &iced::advanced::layout::Limits,
+        scale: f32,
+        connected: &[bool],
+    ) -> (iced::advanced::layout::Node, Size) {
+        let scaled = |size: Size| Size::new(size.width * scale, size.height * scale);
+        let bar_padding = BAR_PADDING * scale;
+        let header_spacing = HEADER_SPACING * scale;
+        let toggle_size = TOGGLE_SIZE * scale;
+        let port_inset = PORT_INSET * scale;
+        let padding = Padding {
+            top: self.padding.top * scale,
+            right: self.padding.right * scale,
+            bottom: self.padding.bottom * scale,
+            left: self.padding.left * scale,
+        };
+
+        let loose = iced::advanced::layout::Limits::new(Size::ZERO, scaled(limits.max()));
+        let layout =
+            |element: &Element<'a, Message, Renderer>| element.as_widget().layout(renderer, &loose);
+
+        let mut icon = self.icon.as_ref().map(layout);
+        let mut title = self.title.as_ref().map(layout);
+        let mut handle = self.drag_handle.as_ref().map(layout);
+        let layout_port = |(port, connected): (&Port<'a, Message, Renderer>, &bool)| PortLayout {
+            label: layout(&port.label),
+            editor: port.editor.as_ref().map(layout),
+            connected: *connected,
+        };
+        let mut inputs: Vec<_> = self.inputs.iter().zip(connected).map(layout_port).collect();
+        let mut outputs: Vec<_> = self
+            .outputs
+            .iter()
+            .zip(&connected[self.inputs.len()..])
+            .map(layout_port)
+            .collect();
+        let mut footer = self.footer.as_ref().map(layout);
+        let mut content = self
+            .content
+            .as_widget()
+            .layout(renderer, &loose.pad(padding));
+
+        let toggle = self
+            .on_collapse
+            .is_some()
+            .then(|| Size::new(toggle_size, toggle_size));
+        let header: Vec<Size> = toggle
+            .into_iter()
+            .chain(
+                icon.iter()
+                    .chain(title.iter())
+                    .chain(handle.iter())
+                    .map(|n| n.size()),
+            )
+            .collect();
+        let (header_width, header_height) = if header.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (
+                header.iter().map(|size| size.width).sum::<f32>()
+                    + header_spacing * (header.len() - 1) as f32
+                    + bar_padding * 2.0,
+                header.iter().map(|size| size.height).fold(0.0, f32::max) + bar_padding * 2.0,
+            )
+        };
+
+        let (footer_width, footer_height) = footer.as_ref().map_or((0.0, 0.0), |footer| {
+            (
+                footer.size().width + bar_padding * 2.0,
+                footer.size().height + bar_padding * 2.0,
+            )
+        });
+
+        let (inputs_width, inputs_height) = port_column_size(&inputs, scale);
+        let (outputs_width, outputs_height) = port_column_size(&outputs, scale);
+        let content_size = content.size();
+        let body_width = inputs_width + content_size.width + padding.horizontal() + outputs_width;
+        let body_height = inputs_height
+            .max(outputs_height)
+            .max(content_size.height + padding.vertical());
+
+        let min_size = Size::new(
+            header_width.max(body_width).max(footer_width),
+            header_height + body_height + footer_height,
+        );
+        let mut size =
+            scaled(limits.resolve(Size::new(min_size.width / scale, min_size.height / scale)));
+        if self.is_collapsed() {
+            size.height = header_height;
+        }
+        let body_height = size.height - header_height - footer_height;
+
+        let mut x = bar_padding;
+        let toggle = toggle.map(|_| {
+            let mut toggle = iced::advanced::layout::Node::new(Size::new(toggle_size, toggle_size));
+            toggle.move_to(Point::new(x, (header_height - toggle_size) / 2.0));
+            x += toggle_size + header_spacing;
+            toggle
+        });
+        for node in icon.iter_mut().chain(title.iter_mut()) {
+            node.move_to(Point::new(x, (header_height - node.size().height) / 2.0));
+            x += node.size().width + header_spacing;
+        }
+        if let Some(handle) = &mut handle {
+            handle.move_to(Point::new(
+                size.width - bar_padding - handle.size().width,
+                (header_height - handle.size().height) / 2.0,
+            ));
+        }
+
+        let mut input_dots = place_ports(&mut inputs, header_height, scale, |_| (port_inset, 0.0));
+        let mut output_dots = place_ports(&mut outputs, header_height, scale, |width| {
+            (size.width - port_inset - width, size.width)
+        });
+        if self.is_collapsed() {
+            // The wires stay attached to the ends of the title bar.
+            let middle = header_height / 2.0;
+            input_dots.iter_mut().for_each(|dot| dot.y = middle);
+            output_dots.iter_mut().for_each(|dot| dot.y = middle);
+        }
+
+        content.move_to(Point::new(
+            inputs_width + padding.left,
+            header_height + padding.top,
+        ));
+        content.align(
+            Alignment::from(self.horizontal_alignment),
+            Alignment::from(self.vertical_alignment),
+            Size::new(
+                size.width - inputs_width - outputs_width - padding.horizontal(),
+                body_height - padding.vertical(),
+            ),
+        );
+
+        if let Some(footer) = &mut footer {
+            footer.move_to(Point::new(
+                bar_padding,
+                size.height - footer_height + bar_padding,
+            ));
+        }
+
+        let elements = std::iter::once(content)
+            .chain(icon)
+            .chain(title)
+            .chain(handle)
+            .chain(
+                inputs
+                    .into_iter()
+                    .chain(outputs)
+                    .flat_map(PortLayout::into_nodes),
+            )
+            .chain(footer)
+            .chain(tooltips(self.tooltip_count()));
+
+        let header = iced::advanced::layout::Node::new(Size::new(size.width, header_height));
+        let toggle = toggle.unwrap_or_else(|| iced::advanced::layout::Node::new(Size::ZERO));
+        let radius = PORT_RADIUS * scale;
+        let dots = input_dots.into_iter().chain(output_dots).map(|center| {
+            let mut dot = iced::advanced::layout::Node::new(Size::new(radius * 2.0, radius * 2.0));
+            dot.move_to(Point::new(center.x - radius, center.y - radius));
+            dot
+        });
+
+        let node = iced::advanced::layout::Node::with_children(
+            size,
+            elements.chain([header, toggle]).chain(dots).collect(),
+        );
+
+        (
+            node,
+            Size::new(min_size.width / scale, min_size.height / scale),
+        )
+    }
+}
+
+/// Draws a rounded rectangle fading out over `blur` around `bounds`, as layers of
+/// growing size whose opacities add up to the one of `color`.
+fn draw_shadow<Renderer>(
+    renderer: &mut Renderer,
+    bounds: Rectangle,
+    border_radius: f32,
+    blur: f32,
+    color: Color,
+) where
+    Renderer: renderer::Renderer,
+{
+    let layers = (blur.ceil() as usize).clamp(1, SHADOW_LAYERS);
+    let color = Color {
+        a: 1.0 - (1.0 - color.a).powf(1.0 / layers as f32),
+        ..color
+    };
+
+    for layer in (0..layers).rev() {
+        let spread = blur * (layer as f32 + 0.5) / layers as f32;
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: bounds.x - spread,
+                    y: bounds.y - spread,
+                    width: bounds.width + spread * 2.0,
+                    height: bounds.height + spread * 2.0,
+                },
+                border_radius: (border_radius + spread).into(),
+                border_width: 0.0,
+                border_color: Color::TRANSPARENT,
+            },
+            color,
+        );
+    }
+}
+
+fn desaturate(color: Color) -> Color {
+    let gray = color.r * 0.299 + color.g * 0.587 + color.b * 0.114;
+    let desaturate = |channel: f32| channel + (gray - channel) * 0.8;
+
+    Color {
+        r: desaturate(color.r),
+        g: desaturate(color.g),
+        b: desaturate(color.b),
+        a: color.a,
+    }
+}
+
+/// Desaturates `color` and makes it more transparent, for nodes that aren't active.
+fn mute(color: Color) -> Color {
+    Color {
+        a: color.a * MUTED_ALPHA,
+        ..desaturate(color)
+    }
+}
+
+fn mute_appearance(appearance: Appearance, mute: fn(Color) -> Color) -> Appearance {
+    let background = |background: Background| match background {
+        Background::Color(color) => Background::Color(mute(color)),
+        gradient => gradient,
+    };
+
+    Appearance {
+        text_color: appearance.text_color.map(mute),
+        background: appearance.background.map(background),
+        border_color: mute(appearance.border_color),
+        header_background: appearance.header_background.map(background),
+        header_text_color: appearance.header_text_color.map(mute),
+        port_color: appearance.port_color.map(mute),
+        shadow_color: appearance.shadow_color.map(mute),
+        glow_color: appearance.glow_color.map(mute),
+        ..appearance
+    }
+}
+
+/// Draws the wire across a bypassed node from the dot at `from` to the one at `to`,
+/// as horizontal runs joined by a vertical one halfway.
+fn draw_bypass<Renderer>(renderer: &mut Renderer, from: Point, to: Point, width: f32, color: Color)
+where
+    Renderer: renderer::Renderer,
+{
+    let middle = (from.x + to.x) / 2.0;
+    let segment = |start: Point, end: Point| Rectangle {
+        x: start.x.min(end.x) - width / 2.0,
+        y: start.y.min(end.y) - width / 2.0,
+        width: (end.x - start.x).abs() + width,
+        height: (end.y - start.y).abs() + width,
+    };
+
+    for bounds in [
+        segment(from, Point::new(middle, from.y)),
+        segment(Point::new(middle, from.y), Point::new(middle, to.y)),
+        segment(Point::new(middle, to.y), to),
+    ] {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border_radius: (width / 2.0).into(),
+                border_width: 0.0,
+                border_color: Color::TRANSPARENT,
+            },
+            color,
+        );
+    }
+}
+
+/// Draws the collapse toggle as a minus sign, or a plus sign while collapsed.
+fn draw_toggle<Renderer>(renderer: &mut Renderer, bounds: Rectangle, collapsed: bool, color: Color)
+where
+    Renderer: renderer::Renderer,
+{
+    let thickness = (bounds.height / 6.0).max(1.0);
+    let length = bounds.width * 0.75;
+    let center = bounds.center();
+    let mut bars = vec![Rectangle {
+        x: center.x - length / 2.0,
+        y: center.y - thickness / 2.0,
+        width: length,
+        height: thickness,
+    }];
+
+    if collapsed {
+        bars.push(Rectangle {
+            x: center.x - thickness / 2.0,
+            y: center.y - length / 2.0,
+            width: thickness,
+            height: length,
+        });
+    }
+
+    for bar in bars {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: bar,
+                border_radius: (thickness / 2.0).into(),
+                border_width: 0.0,
+                border_color: Color::TRANSPARENT,
+            },
+            color,
+        );
+    }
+}
+
+/// The element kinds a node is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Content,
+    Header,
+    /// The label of the port with the index among the inputs followed by the outputs.
+    Label(usize),
+    Editor(usize),
+    Footer,
+    /// Tooltips are laid out and drawn on demand by [`tooltip::draw`].
+    StatusMessage,
+    Tooltip,
+    PortTooltip(usize),
+}
+
+/// Empty layouts standing in for the given number of tooltips among the layouts of
+/// the elements.
+fn tooltips(count: usize) -> impl Iterator<Item = iced::advanced::layout::Node> {
+    std::iter::repeat_with(|| iced::advanced::layout::Node::new(Size::ZERO)).take(count)
+}
+
+/// The layouts of the label and of the editor of a port, only one of which is shown.
+struct PortLayout {
+    label: iced::advanced::layout::Node,
+    editor: Option<iced::advanced::layout::Node>,
+    connected: bool,
+}
+
+impl PortLayout {
+    fn shown(&self) -> &iced::advanced::layout::Node {
+        match &self.editor {
+            Some(editor) if !self.connected => editor,
+            _ => &self.label,
+        }
+    }
+
+    fn into_nodes(self) -> impl Iterator<Item = iced::advanced::layout::Node> {
+        std::iter::once(self.label).chain(self.editor)
+    }
+}
+
+/// Width of a column of ports including the space to the node edge and to the content,
+/// and its height including the space above and below, with the spacing at `scale`.
+fn port_column_size(ports: &[PortLayout], scale: f32) -> (f32, f32) {
+    if ports.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let width = ports
+        .iter()
+        .map(|port| port.shown().size().width)
+        .fold(0.0, f32::max);
+    let height = ports
+        .iter()
+        .map(|port| {
+            port.shown().size().height.max(PORT_RADIUS * 2.0 * scale) + PORT_SPACING * scale
+        })
+        .sum::<f32>();
+
+    (
+        (PORT_INSET + PORT_GAP) * scale + width,
+        height + PORT_SPACING * scale,
+    )
+}
+
+/// Stacks the port labels and editors below `top` with the spacing at `scale`, with
+/// `horizontal` giving the x of an element and of its dot from the element width, and
+/// returns the centers of the dots.
+fn place_ports(
+    ports: &mut [PortLayout],
+    top: f32,
+    scale: f32,
+    horizontal: impl Fn(f32) -> (f32, f32),
+) -> Vec<Point> {
+    let mut y = top + PORT_SPACING * scale;
+
+    ports
+        .iter_mut()
+        .map(|port| {
+            let shown = port.shown().size();
+            let row = shown.height.max(PORT_RADIUS * 2.0 * scale);
+
+            for node in std::iter::once(&mut port.label).chain(port.editor.as_mut()) {
+                let size = node.size();
+                node.move_to(Point::new(
+                    horizontal(size.width).0,
+                    y + (row - size.height) / 2.0,
+                ));
+            }
+
+            let center = Point::new(horizontal(shown.width).1, y + row / 2.0);
+            y += row + PORT_SPACING * scale;
+            center
+        })
+        .collect()
+}
+
+pub fn node<'a, Id, Message, Renderer>(
     content: impl Into<Element<'a, Message, Renderer>>,
-) -> Node<'a, Message, Renderer>
+) -> Node<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
@@ -129,8 +1061,10 @@ where
     Node::new(content)
 }
 
-impl<'a, Message, Renderer> ScalableWidget<Message, Renderer> for Node<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> ScalableWidget<Id, Message, Renderer>
+    for Node<'a, Id, Message, Renderer>
 where
+    Id: PartialEq,
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
@@ -138,8 +1072,9 @@ where
         &self,
         renderer: &Renderer,
         limits: &iced::advanced::layout::Limits,
-        scale: f32,
+        context: &LayoutContext<'_, Id>,
     ) -> iced::advanced::layout::Node {
+        let scale = context.scale;
         let limits = limits
             .loose()
             .max_width(self.max_width)
@@ -147,45 +1082,192 @@ where
             .width(self.width)
             .height(self.height);
 
-        let mut content = self
-            .content
-            .as_widget()
-            .layout(renderer, &limits.pad(self.padding).loose());
+        let is_connected = |output: bool, index: usize| {
+            context.connected.iter().any(|endpoint| match endpoint {
+                Endpoint::Input(id, i) => !output && *i == index && self.id.as_ref() == Some(id),
+                Endpoint::Output(id, i) => output && *i == index && self.id.as_ref() == Some(id),
+                Endpoint::Point(_) => false,
+            })
+        };
+        let connected: Vec<bool> = (0..self.inputs.len())
+            .map(|i| is_connected(false, i))
+            .chain((0..self.outputs.len()).map(|i| is_connected(true, i)))
+            .collect();
 
-        let padding = self.padding.fit(content.size(), limits.max());
-        let size = limits.pad(padding).resolve(content.size());
-        let size = Size::new(size.width * scale, size.height * scale);
+        let (node, min_size) = if self.is_structured() {
+            self.layout_structured(renderer, &limits, scale, &connected)
+        } else {
+            let mut content = self
+                .content
+                .as_widget()
+                .layout(renderer, &limits.pad(self.padding).loose());
 
-        content.move_to(Point::new(padding.left.into(), padding.top.into()));
-        content.align(
-            Alignment::from(self.horizontal_alignment),
-            Alignment::from(self.vertical_alignment),
-            size,
-        );
+            let padding = self.padding.fit(content.size(), limits.max());
+            let size = limits.pad(padding).resolve(content.size());
+            let size = Size::new(size.width * scale, size.height * scale);
+
+            content.move_to(Point::new(padding.left, padding.top));
+            content.align(
+                Alignment::from(self.horizontal_alignment),
+                Alignment::from(self.vertical_alignment),
+                size,
+            );
 
-        let node = iced::advanced::layout::Node::with_children(size, vec![content]);
+            let min_size = Size::new(
+                content.size().width + padding.horizontal(),
+                content.size().height + padding.vertical(),
+            );
+
+            (
+                iced::advanced::layout::Node::with_children(
+                    size,
+                    std::iter::once(content)
+                        .chain(tooltips(self.tooltip_count()))
+                        .collect(),
+                ),
+                min_size,
+            )
+        };
+
+        *self.measurements.lock().expect("Could not lock mutex") = Measurements {
+            scale,
+            min_size,
+            connected,
+        };
 
         node.translate(Vector::new(self.position.x, self.position.y) * scale)
     }
+
+    fn port_anchors(&self, layout: Layout<'_>) -> PortAnchors {
+        let mut dots = self.chrome(layout).skip(2).map(|dot| dot.bounds().center());
+
+        PortAnchors {
+            inputs: dots.by_ref().take(self.inputs.len()).collect(),
+            outputs: dots.take(self.outputs.len()).collect(),
+        }
+    }
+
+    fn id(&self) -> Option<&Id> {
+        self.id.as_ref()
+    }
+
+    fn is_obstacle(&self) -> bool {
+        true
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn z_index(&self) -> i32 {
+        self.z_index
+    }
+
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn is_draggable_at(&self, layout: Layout<'_>, position: Point) -> bool {
+        let region = match self.drag_region {
+            DragRegion::Node => Some(layout.bounds()),
+            DragRegion::Header if self.has_header() => {
+                self.chrome(layout).next().map(|header| header.bounds())
+            }
+            DragRegion::Header => Some(layout.bounds()),
+            DragRegion::Handle => self.drag_handle.as_ref().and_then(|_| {
+                layout
+                    .children()
+                    .nth(self.header_elements().end - 1)
+                    .map(|handle| handle.bounds())
+            }),
+        };
+
+        region.is_some_and(|region| region.contains(position))
+    }
+
+    fn tooltip_at(&self, layout: Layout<'_>, position: Point) -> Option<usize> {
+        let parts = self.parts();
+        let find = |part: Part| parts.iter().position(|p| *p == part);
+        let bounds = layout.bounds();
+        let badge = self.badge_bounds(bounds);
+
+        if self.status != Status::Idle && badge.center().distance(position) <= badge.width / 2.0 {
+            if let Some(index) = find(Part::StatusMessage) {
+                return Some(index);
+            }
+        }
+
+        if let Some(index) = self
+            .port_at(layout, position)
+            .and_then(|port| find(Part::PortTooltip(port)))
+        {
+            return Some(index);
+        }
+
+        find(Part::Tooltip).filter(|_| bounds.contains(position))
+    }
+
+    fn draw_tooltip(
+        &self,
+        tree: &widget::Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: TooltipStyle,
+        _layout: Layout<'_>,
+        tooltip: usize,
+        position: Point,
+        bounds: Rectangle,
+    ) {
+        let elements = self.elements();
+        let (Some(element), Some(tree)) = (elements.get(tooltip), tree.children.get(tooltip))
+        else {
+            return;
+        };
+        let status_color = (self.parts().get(tooltip) == Some(&Part::StatusMessage))
+            .then(|| theme.status_color(&self.style, self.status))
+            .flatten();
+
+        tooltip::draw(
+            element,
+            tree,
+            renderer,
+            theme,
+            TooltipStyle {
+                border_color: status_color.unwrap_or(style.border_color),
+                ..style
+            },
+            position,
+            bounds,
+        );
+    }
 }
 
-impl<'a, Message, Renderer> Widget<Message, Renderer> for Node<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> Widget<Message, Renderer> for Node<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
     Renderer::Theme: StyleSheet,
 {
-    fn children(&self) -> Vec<iced::advanced::widget::Tree> {
-        vec![iced::advanced::widget::Tree::new(&self.content)]
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
     }
 
-    fn diff(&self, tree: &mut iced::advanced::widget::Tree) {
-        tree.diff_children(std::slice::from_ref(&self.content))
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
     }
 
-    fn state(&self) -> widget::tree::State {
-        widget::tree::State::new(NodeState {
-            drag_start_position: None,
-        })
+    fn children(&self) -> Vec<iced::advanced::widget::Tree> {
+        self.elements()
+            .into_iter()
+            .map(iced::advanced::widget::Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut iced::advanced::widget::Tree) {
+        tree.diff_children_custom(
+            &self.elements(),
+            |tree, element| tree.diff(element.as_widget()),
+            |element| iced::advanced::widget::Tree::new(element.as_widget()),
+        )
     }
 
     fn layout(
@@ -206,8 +1288,65 @@ where
         cursor: iced::advanced::mouse::Cursor,
         viewport: &iced::Rectangle,
     ) {
-        let style = theme.appearance(&self.style);
+        let mut style = if self.selected {
+            theme.selected(&self.style)
+        } else if tree.state.downcast_ref::<State>().hovered {
+            theme.hovered(&self.style)
+        } else {
+            theme.appearance(&self.style)
+        };
+        let mut status_color = theme.status_color(&self.style, self.status);
+        if let Some(color) = status_color {
+            style.border_color = color;
+        }
+        let bypass_color = self
+            .inputs
+            .first()
+            .and_then(|port| port.color)
+            .or(style.port_color)
+            .unwrap_or(style.border_color);
+        let muted = !self.mode.is_evaluated();
+        let veil = if muted {
+            theme.muted_veil(&self.style)
+        } else {
+            None
+        };
+        // The veil fades the node and its contents at once, leaving only the colors
+        // to desaturate and what reaches past the node to fade.
+        let fade: fn(Color) -> Color = if veil.is_some() { desaturate } else { mute };
+        if muted {
+            style = Appearance {
+                shadow_color: style.shadow_color.map(mute),
+                glow_color: style.glow_color.map(mute),
+                ..mute_appearance(style, fade)
+            };
+            status_color = status_color.map(mute);
+        }
         let bounds = layout.bounds();
+        let scale = self
+            .measurements
+            .lock()
+            .expect("Could not lock mutex")
+            .scale;
+
+        if let Some(color) = style.shadow_color {
+            draw_shadow(
+                renderer,
+                bounds + style.shadow_offset * scale,
+                style.border_radius,
+                style.shadow_blur * scale,
+                color,
+            );
+        }
+        if let Some(color) = style.glow_color {
+            draw_shadow(
+                renderer,
+                bounds,
+                style.border_radius,
+                style.glow_width * scale,
+                color,
+            );
+        }
 
         if style.background.is_some() || style.border_width > 0.0 {
             renderer.fill_quad(
@@ -223,17 +1362,133 @@ where
             );
         }
 
-        self.content.as_widget().draw(
-            tree,
-            renderer,
-            theme,
-            &renderer::Style {
-                text_color: style.text_color.unwrap_or(renderer_style.text_color),
-            },
-            layout.children().next().unwrap(),
-            cursor,
-            viewport,
-        );
+        let collapsed = self.is_collapsed();
+        let text_color = style.text_color.unwrap_or(renderer_style.text_color);
+        let header_text_color = style.header_text_color.unwrap_or(text_color);
+        let mut chrome = self.chrome(layout);
+
+        if let (Some(header), Some(background)) = (chrome.next(), style.header_background) {
+            let header = header.bounds();
+            let inset = style.border_width;
+            let radius = (style.border_radius - inset).max(0.0);
+            let (bottom_inset, bottom_radius) = if collapsed {
+                (inset, radius)
+            } else {
+                (0.0, 0.0)
+            };
+
+            if header.height > 0.0 {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: header.x + inset,
+                            y: header.y + inset,
+                            width: header.width - inset * 2.0,
+                            height: header.height - inset - bottom_inset,
+                        },
+                        border_radius: [radius, radius, bottom_radius, bottom_radius].into(),
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT,
+                    },
+                    background,
+                );
+            }
+        }
+
+        if let Some(toggle) = chrome.next().filter(|_| self.on_collapse.is_some()) {
+            draw_toggle(renderer, toggle.bounds(), collapsed, header_text_color);
+        }
+
+        if self.mode == Mode::Bypassed && !self.inputs.is_empty() && !self.outputs.is_empty() {
+            let mut dots = self.chrome(layout).skip(2);
+            let from = dots.next();
+            let to = dots.nth(self.inputs.len() - 1);
+
+            if let (Some(from), Some(to)) = (from, to) {
+                draw_bypass(
+                    renderer,
+                    from.bounds().center(),
+                    to.bounds().center(),
+                    BYPASS_WIDTH * scale,
+                    bypass_color,
+                );
+            }
+        }
+
+        let ports = self.inputs.iter().chain(self.outputs.iter());
+        for (port, dot) in ports.zip(chrome) {
+            let dot = dot.bounds();
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: dot,
+                    border_radius: (dot.width / 2.0).into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                port.color
+                    .map(|color| if muted { fade(color) } else { color })
+                    .or(style.port_color)
+                    .unwrap_or(style.border_color),
+            );
+        }
+
+        let header_elements = self.header_elements();
+        let visibility = self.visibility();
+
+        for (i, ((element, tree), layout)) in self
+            .elements()
+            .into_iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .enumerate()
+            .filter(|(i, _)| visibility[*i])
+        {
+            element.as_widget().draw(
+                tree,
+                renderer,
+                theme,
+                &renderer::Style {
+                    text_color: if header_elements.contains(&i) {
+                        header_text_color
+                    } else {
+                        text_color
+                    },
+                },
+                layout,
+                cursor,
+                viewport,
+            );
+        }
+
+        if let Some(veil) = veil {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    border_radius: style.border_radius.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                veil,
+            );
+        }
+
+        if let Some(color) = status_color {
+            let badge = self.badge_bounds(bounds);
+            let ring = match style.background {
+                Some(Background::Color(background)) => background,
+                _ => Color::TRANSPARENT,
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: badge,
+                    border_radius: (badge.width / 2.0).into(),
+                    border_width: (badge.width / 6.0).max(1.0),
+                    border_color: ring,
+                },
+                color,
+            );
+        }
     }
 
     fn on_event(
@@ -247,29 +1502,88 @@ where
         shell: &mut Shell<'_, Message>,
         viewport: &Rectangle<f32>,
     ) -> event::Status {
-        let mut status = event::Status::Ignored;
-        let mut state = tree.state.downcast_mut::<NodeState>();
-
-        if let Some(cursor_position) = cursor.position() {
-            if let Some(start) = state.drag_start_position {
-                match event {
-                    Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                        state.drag_start_position = None;
-                    }
-                    Event::Mouse(mouse::Event::CursorMoved { .. }) => {
-                        let delta = cursor_position - start;
-                        state.drag_start_position = Some(cursor_position);
-                        if let Some(f) = &self.on_translate {
-                            let message = f((delta.x, delta.y));
-                            shell.publish(message);
-                        }
-                        status = event::Status::Captured;
-                    }
-                    _ => {}
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                state.hovered = cursor.is_over(layout.bounds());
+            }
+            Event::Mouse(mouse::Event::CursorLeft) => {
+                state.hovered = false;
+            }
+            _ => {}
+        }
+
+        // A release ends the resize wherever the cursor is, and even once `on_resize` is gone.
+        if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event {
+            if state.resize.take().is_some() {
+                return event::Status::Captured;
+            }
+        }
+
+        if let (Some(resize), Some(on_resize)) = (&state.resize, &self.on_resize) {
+            if let Event::Mouse(mouse::Event::CursorMoved { position }) = event {
+                let measurements = self
+                    .measurements
+                    .lock()
+                    .expect("Could not lock mutex")
+                    .clone();
+                let delta = (position - resize.origin) * (1.0 / measurements.scale);
+                let mut size = resize.size;
+
+                if resize.handle.resizes_width() {
+                    size.width = (size.width + delta.x).max(measurements.min_size.width);
                 }
-            } else {
-                status = self.content.as_widget_mut().on_event(
-                    &mut tree.children[0],
+                if resize.handle.resizes_height() {
+                    size.height = (size.height + delta.y).max(measurements.min_size.height);
+                }
+
+                shell.publish(on_resize(size));
+                return event::Status::Captured;
+            }
+        }
+
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+            let bounds = layout.bounds();
+            let handle = cursor
+                .position()
+                .and_then(|position| Some((position, self.handle_at(bounds, position)?)));
+
+            if let Some((origin, handle)) = handle {
+                let scale = self
+                    .measurements
+                    .lock()
+                    .expect("Could not lock mutex")
+                    .scale;
+                state.resize = Some(Resize {
+                    handle,
+                    origin,
+                    size: Size::new(bounds.width / scale, bounds.height / scale),
+                });
+                return event::Status::Captured;
+            }
+        }
+
+        if let (Some(toggle), Some(on_collapse)) = (self.toggle_bounds(layout), &self.on_collapse) {
+            if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+                if cursor.is_over(toggle) {
+                    shell.publish(on_collapse(!self.collapsed));
+                    return event::Status::Captured;
+                }
+            }
+        }
+
+        let visible = self.visibility();
+
+        self.elements_mut()
+            .into_iter()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .zip(visible)
+            .filter(|(_, visible)| *visible)
+            .map(|(((element, tree), layout), _)| {
+                element.as_widget_mut().on_event(
+                    tree,
                     event.clone(),
                     layout,
                     cursor,
@@ -278,22 +1592,8 @@ where
                     shell,
                     viewport,
                 )
-            }
-        }
-
-        if let Some(cursor_position) = cursor.position() {
-            if status == event::Status::Ignored && layout.bounds().contains(cursor_position) {
-                match event {
-                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
-                        state.drag_start_position = Some(cursor_position);
-                        status = event::Status::Captured;
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        status
+            })
+            .fold(event::Status::Ignored, event::Status::merge)
     }
 
     fn mouse_interaction(
@@ -304,13 +1604,40 @@ where
         viewport: &Rectangle,
         renderer: &Renderer,
     ) -> mouse::Interaction {
-        self.content.as_widget().mouse_interaction(
-            &tree.children[0],
-            layout,
-            cursor,
-            viewport,
-            renderer,
-        )
+        let state = tree.state.downcast_ref::<State>();
+        let handle = state
+            .resize
+            .as_ref()
+            .map(|resize| resize.handle)
+            .or_else(|| {
+                cursor
+                    .position()
+                    .and_then(|position| self.handle_at(layout.bounds(), position))
+            });
+
+        if let Some(handle) = handle {
+            return handle.interaction();
+        }
+
+        if let Some(toggle) = self.toggle_bounds(layout) {
+            if cursor.is_over(toggle) {
+                return mouse::Interaction::Pointer;
+            }
+        }
+
+        self.elements()
+            .into_iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .zip(self.visibility())
+            .filter(|(_, visible)| *visible)
+            .map(|(((element, tree), layout), _)| {
+                element
+                    .as_widget()
+                    .mouse_interaction(tree, layout, cursor, viewport, renderer)
+            })
+            .max()
+            .unwrap_or_default()
     }
 
     fn width(&self) -> Length {
@@ -322,14 +1649,229 @@ where
     }
 }
 
-impl<'a, Message, Renderer> From<Node<'a, Message, Renderer>>
-    for GraphNodeElement<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> From<Node<'a, Id, Message, Renderer>>
+    for GraphNodeElement<'a, Id, Message, Renderer>
 where
+    Id: PartialEq + 'a,
     Message: 'a,
     Renderer: renderer::Renderer + 'a,
     Renderer::Theme: StyleSheet,
 {
-    fn from(node: Node<'a, Message, Renderer>) -> Self {
+    fn from(node: Node<'a, Id, Message, Renderer>) -> Self {
         Self::new(node)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{port, ConnectionRouting};
+    use iced::advanced::renderer::Null;
+    use iced::widget::Space;
+
+    impl StyleSheet for () {
+        type Style = ();
+
+        fn appearance(&self, _style: &Self::Style) -> Appearance {
+            Appearance::default()
+        }
+    }
+
+    fn layout<Message>(
+        node: &Node<'_, usize, Message, Null>,
+        scale: f32,
+    ) -> iced::advanced::layout::Node {
+        let context = LayoutContext {
+            scale,
+            routing: ConnectionRouting::default(),
+            obstacles: &[],
+            ports: &[],
+            connected: &[],
+            route_cache: None,
+        };
+        let limits = iced::advanced::layout::Limits::new(Size::ZERO, Size::INFINITY);
+
+        ScalableWidget::layout(node, &Null, &limits, &context)
+    }
+
+    struct NoClipboard;
+
+    impl Clipboard for NoClipboard {
+        fn read(&self) -> Option<String> {
+            None
+        }
+
+        fn write(&mut self, _contents: String) {}
+    }
+
+    fn contains(outer: Rectangle, inner: Rectangle) -> bool {
+        inner.x >= outer.x
+            && inner.y >= outer.y
+            && inner.x + inner.width <= outer.x + outer.width + 1e-3
+            && inner.y + inner.height <= outer.y + outer.height + 1e-3
+    }
+
+    #[test]
+    fn structured_nodes_keep_their_elements_inside_when_zoomed_out() {
+        let node: Node<'_, usize, (), Null> = node(Space::new(40.0, 20.0))
+            .id(0)
+            .title(Space::new(30.0, 10.0))
+            .input(port(Space::new(20.0, 10.0)))
+            .output(port(Space::new(24.0, 10.0)))
+            .footer(Space::new(50.0, 10.0));
+        let scale = 0.5;
+        let laid_out = layout(&node, scale);
+        let layout = Layout::new(&laid_out);
+        let bounds = layout.bounds();
+        let children: Vec<Rectangle> = layout.children().map(|child| child.bounds()).collect();
+        let [content, title, input, output, footer, header, ..] = children[..] else {
+            panic!("unexpected children: {children:?}");
+        };
+
+        for element in [content, title, input, output, footer] {
+            assert!(contains(bounds, element), "{element:?} outside {bounds:?}");
+        }
+        assert!(contains(header, title));
+        assert!(title.y + title.height <= content.y);
+        assert!(content.y + content.height <= footer.y);
+        assert!(input.x + input.width <= content.x);
+        assert!(content.x + content.width <= output.x);
+        assert_eq!(
+            output.x + output.width,
+            bounds.x + bounds.width - PORT_INSET * scale
+        );
+
+        let anchors = node.port_anchors(layout);
+        assert_eq!(anchors.inputs[0].x, bounds.x);
+        assert_eq!(anchors.outputs[0].x, bounds.x + bounds.width);
+    }
+
+    #[test]
+    fn structured_nodes_measure_their_minimum_size_in_graph_units() {
+        let node: Node<'_, usize, (), Null> = node(Space::new(40.0, 20.0))
+            .title(Space::new(30.0, 10.0))
+            .input(port(Space::new(20.0, 10.0)));
+
+        layout(&node, 1.0);
+        let unscaled = node.measurements.lock().unwrap().min_size;
+        let laid_out = layout(&node, 2.0);
+        let zoomed = node.measurements.lock().unwrap().min_size;
+
+        // Only the spacing grows with the zoom, the elements keep their size on screen.
+        assert!(zoomed.width < unscaled.width);
+        assert_eq!(laid_out.size().width, zoomed.width * 2.0);
+        assert_eq!(laid_out.size().height, zoomed.height * 2.0);
+    }
+
+    fn resizable(node: Node<'_, usize, Size, Null>) -> Node<'_, usize, Size, Null> {
+        node.width(Length::Fixed(100.0))
+            .height(Length::Fixed(60.0))
+            .on_resize(|size| size)
+    }
+
+    #[test]
+    fn handles_are_found_along_the_right_and_bottom_edges() {
+        let bounds = Rectangle::new(Point::new(10.0, 20.0), Size::new(100.0, 60.0));
+        let reach = HANDLE_WIDTH / 2.0;
+
+        let fixed: Node<'_, usize, Size, Null> = node(Space::new(40.0, 20.0));
+        assert_eq!(fixed.handle_at(bounds, Point::new(110.0, 50.0)), None);
+
+        let collapsed = resizable(node(Space::new(40.0, 20.0)))
+            .title(Space::new(30.0, 10.0))
+            .collapsed(true);
+        assert_eq!(
+            collapsed.handle_at(bounds, Point::new(60.0, 80.0)),
+            None,
+            "collapsed nodes only resize their width"
+        );
+        assert_eq!(
+            collapsed.handle_at(bounds, Point::new(110.0, 80.0)),
+            Some(Handle::Right)
+        );
+
+        let node = resizable(node(Space::new(40.0, 20.0)));
+        let at = |x, y| node.handle_at(bounds, Point::new(x, y));
+        assert_eq!(at(110.0, 50.0), Some(Handle::Right));
+        assert_eq!(at(110.0 - reach, 50.0), Some(Handle::Right));
+        assert_eq!(at(60.0, 80.0 + reach), Some(Handle::Bottom));
+        assert_eq!(at(110.0, 80.0), Some(Handle::Corner));
+        assert_eq!(at(110.0 + reach, 80.0 + reach), Some(Handle::Corner));
+        assert_eq!(at(60.0, 50.0), None);
+        assert_eq!(at(110.0 + reach + 1.0, 50.0), None);
+        assert_eq!(at(110.0, 10.0), None);
+    }
+
+    #[test]
+    fn handles_show_the_cursor_of_their_direction() {
+        let node = resizable(node(Space::new(40.0, 20.0)));
+        let laid_out = layout(&node, 0.5);
+        let layout = Layout::new(&laid_out);
+        let tree = widget::Tree::new(&node as &dyn Widget<Size, Null>);
+        let viewport = Rectangle::new(Point::ORIGIN, Size::INFINITY);
+        let interaction = |x, y| {
+            node.mouse_interaction(
+                &tree,
+                layout,
+                mouse::Cursor::Available(Point::new(x, y)),
+                &viewport,
+                &Null,
+            )
+        };
+
+        assert_eq!(
+            interaction(50.0, 15.0),
+            mouse::Interaction::ResizingHorizontally
+        );
+        assert_eq!(
+            interaction(25.0, 30.0),
+            mouse::Interaction::ResizingVertically
+        );
+        assert_eq!(interaction(50.0, 30.0), mouse::Interaction::Crosshair);
+        assert_eq!(interaction(25.0, 15.0), mouse::Interaction::Idle);
+    }
+
+    #[test]
+    fn resizing_publishes_the_size_in_graph_units_down_to_the_minimum() {
+        let mut node = resizable(node(Space::new(40.0, 20.0)));
+        let scale = 0.5;
+        let laid_out = layout(&node, scale);
+        let layout = Layout::new(&laid_out);
+        assert_eq!(layout.bounds().size(), Size::new(50.0, 30.0));
+
+        let mut tree = widget::Tree::new(&node as &dyn Widget<Size, Null>);
+        let mut messages = Vec::new();
+        let mut shell = Shell::new(&mut messages);
+        let viewport = Rectangle::new(Point::ORIGIN, Size::INFINITY);
+        let mut send = |event: mouse::Event, position: Point, shell: &mut Shell<'_, Size>| {
+            node.on_event(
+                &mut tree,
+                Event::Mouse(event),
+                layout,
+                mouse::Cursor::Available(position),
+                &Null,
+                &mut NoClipboard,
+                shell,
+                &viewport,
+            )
+        };
+
+        let corner = Point::new(50.0, 30.0);
+        let pressed = mouse::Event::ButtonPressed(mouse::Button::Left);
+        assert_eq!(send(pressed, corner, &mut shell), event::Status::Captured);
+
+        let position = Point::new(60.0, -70.0);
+        send(mouse::Event::CursorMoved { position }, position, &mut shell);
+        let released = mouse::Event::ButtonReleased(mouse::Button::Left);
+        assert_eq!(
+            send(released, position, &mut shell),
+            event::Status::Captured
+        );
+
+        let position = Point::new(70.0, 70.0);
+        send(mouse::Event::CursorMoved { position }, position, &mut shell);
+
+        // 10 pixels at half size are 20 graph units, and the height stops at the content.
+        assert_eq!(messages, vec![Size::new(120.0, 20.0)]);
+    }
+}
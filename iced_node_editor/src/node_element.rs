@@ -1,21 +1,28 @@
-use iced::advanced::{layout, renderer, Widget};
+use iced::advanced::{layout, renderer, widget, Layout, Widget};
+
+use iced::{Point, Rectangle};
+
+use crate::node::Mode;
+use crate::port::{Endpoint, PortAnchors};
+use crate::routing::{ConnectionRouting, RouteCache};
+use crate::tooltip::TooltipStyle;
 use std::borrow::Borrow;
 
-pub struct GraphNodeElement<'a, Message, Renderer> {
-    widget: Box<dyn GraphWidget<'a, Message, Renderer> + 'a>,
+pub struct GraphNodeElement<'a, Id, Message, Renderer> {
+    widget: Box<dyn GraphWidget<'a, Id, Message, Renderer> + 'a>,
 }
 
-pub trait GraphWidget<'a, Message, Renderer: renderer::Renderer>:
-    Widget<Message, Renderer> + ScalableWidget<Message, Renderer>
+pub trait GraphWidget<'a, Id, Message, Renderer: renderer::Renderer>:
+    Widget<Message, Renderer> + ScalableWidget<Id, Message, Renderer>
 {
     fn as_widget(&self) -> &(dyn Widget<Message, Renderer> + 'a);
     fn as_widget_mut(&mut self) -> &mut (dyn Widget<Message, Renderer> + 'a);
-    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer> + 'a);
+    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Id, Message, Renderer> + 'a);
 }
 
-impl<'a, T, Message, Renderer: renderer::Renderer> GraphWidget<'a, Message, Renderer> for T
+impl<'a, T, Id, Message, Renderer: renderer::Renderer> GraphWidget<'a, Id, Message, Renderer> for T
 where
-    T: Widget<Message, Renderer> + ScalableWidget<Message, Renderer> + 'a,
+    T: Widget<Message, Renderer> + ScalableWidget<Id, Message, Renderer> + 'a,
 {
     fn as_widget(&self) -> &(dyn Widget<Message, Renderer> + 'a) {
         self
@@ -25,23 +32,100 @@ where
         self
     }
 
-    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer> + 'a) {
+    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Id, Message, Renderer> + 'a) {
         self
     }
 }
 
-pub trait ScalableWidget<Message, Renderer>
+/// Settings of the [`GraphContainer`](crate::GraphContainer) that its elements are laid out with.
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutContext<'a, Id> {
+    pub scale: f32,
+    pub routing: ConnectionRouting,
+    /// Bounds of the elements laid out first, which connections are routed around.
+    pub obstacles: &'a [Rectangle],
+    /// Ports of the elements laid out first, which connections can be attached to.
+    pub ports: &'a [(Id, PortAnchors)],
+    /// Ports that connections are attached to.
+    pub connected: &'a [Endpoint<Id>],
+    pub route_cache: Option<&'a RouteCache>,
+}
+
+pub trait ScalableWidget<Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
 {
-    fn layout(&self, renderer: &Renderer, limits: &layout::Limits, scale: f32) -> layout::Node;
+    fn layout(
+        &self,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+        context: &LayoutContext<'_, Id>,
+    ) -> layout::Node;
+
+    /// The ends of the element, if it connects others.
+    fn endpoints(&self) -> Vec<&Endpoint<Id>> {
+        Vec::new()
+    }
+
+    /// Where connections attach to the ports of the element laid out as `layout`.
+    fn port_anchors(&self, _layout: Layout<'_>) -> PortAnchors {
+        PortAnchors::default()
+    }
+
+    /// Whether the element is laid out before the others, which are routed around it.
+    fn is_obstacle(&self) -> bool {
+        false
+    }
+
+    fn id(&self) -> Option<&Id> {
+        None
+    }
+
+    fn is_selected(&self) -> bool {
+        false
+    }
+
+    /// Whether a press at `position` can start dragging the element laid out as `layout`.
+    fn is_draggable_at(&self, _layout: Layout<'_>, _position: Point) -> bool {
+        true
+    }
+
+    /// Elements with a higher z-index are drawn above the others and get events first.
+    fn z_index(&self) -> i32 {
+        0
+    }
+
+    fn mode(&self) -> Mode {
+        Mode::Active
+    }
+
+    /// The tooltip of the element laid out as `layout` under `position`, as a key passed
+    /// back to [`draw_tooltip`](Self::draw_tooltip).
+    fn tooltip_at(&self, _layout: Layout<'_>, _position: Point) -> Option<usize> {
+        None
+    }
+
+    /// Draws the given tooltip next to `position`, in screen space and within `bounds`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_tooltip(
+        &self,
+        _tree: &widget::Tree,
+        _renderer: &mut Renderer,
+        _theme: &Renderer::Theme,
+        _style: TooltipStyle,
+        _layout: Layout<'_>,
+        _tooltip: usize,
+        _position: Point,
+        _bounds: Rectangle,
+    ) {
+    }
 }
 
-impl<'a, Message, Renderer> GraphNodeElement<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> GraphNodeElement<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
 {
-    pub fn new(widget: impl GraphWidget<'a, Message, Renderer> + 'a) -> Self {
+    pub fn new(widget: impl GraphWidget<'a, Id, Message, Renderer> + 'a) -> Self {
         Self {
             widget: Box::new(widget),
         }
@@ -55,27 +139,27 @@ where
         self.widget.as_widget_mut()
     }
 
-    pub fn as_scalable_widget(&self) -> &dyn ScalableWidget<Message, Renderer> {
+    pub fn as_scalable_widget(&self) -> &dyn ScalableWidget<Id, Message, Renderer> {
         self.widget.as_scalable_widget()
     }
 }
 
-impl<'a, Message, Renderer> Borrow<dyn Widget<Message, Renderer> + 'a>
-    for GraphNodeElement<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> Borrow<dyn Widget<Message, Renderer> + 'a>
+    for GraphNodeElement<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
 {
     fn borrow(&self) -> &(dyn Widget<Message, Renderer> + 'a) {
-        self.widget.as_widget().borrow()
+        self.widget.as_widget()
     }
 }
 
-impl<'a, Message, Renderer> Borrow<dyn Widget<Message, Renderer> + 'a>
-    for &GraphNodeElement<'a, Message, Renderer>
+impl<'a, Id, Message, Renderer> Borrow<dyn Widget<Message, Renderer> + 'a>
+    for &GraphNodeElement<'a, Id, Message, Renderer>
 where
     Renderer: renderer::Renderer,
 {
     fn borrow(&self) -> &(dyn Widget<Message, Renderer> + 'a) {
-        self.widget.as_widget().borrow()
+        self.widget.as_widget()
     }
 }
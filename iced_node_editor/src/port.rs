@@ -0,0 +1,107 @@
+use iced::advanced::renderer;
+use iced::{Color, Element, Point, Vector};
+
+use crate::node_element::LayoutContext;
+
+/// An input or output of a [`Node`](crate::Node), drawn as a dot on the node's
+/// edge next to its label.
+pub struct Port<'a, Message, Renderer> {
+    pub(crate) label: Element<'a, Message, Renderer>,
+    pub(crate) color: Option<Color>,
+    pub(crate) editor: Option<Element<'a, Message, Renderer>>,
+    pub(crate) tooltip: Option<Element<'a, Message, Renderer>>,
+}
+
+impl<'a, Message, Renderer> Port<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    pub fn new<T>(label: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        Port {
+            label: label.into(),
+            color: None,
+            editor: None,
+            tooltip: None,
+        }
+    }
+
+    /// Color of the dot, defaulting to the `port_color` of the node style.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Shows the given element, such as a number field or a checkbox, instead of the
+    /// label while nothing is connected to the port.
+    pub fn editor<T>(mut self, editor: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.editor = Some(editor.into());
+        self
+    }
+
+    /// Shows the given element in a tooltip while the dot or the label is hovered.
+    pub fn tooltip<T>(mut self, tooltip: T) -> Self
+    where
+        T: Into<Element<'a, Message, Renderer>>,
+    {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+}
+
+pub fn port<'a, Message, Renderer>(
+    label: impl Into<Element<'a, Message, Renderer>>,
+) -> Port<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    Port::new(label)
+}
+
+/// One end of a [`connection`](crate::connection), either a point in graph
+/// space or a port of the node with the given id, which the wire follows as the node
+/// moves, collapses or resizes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Endpoint<Id> {
+    Point(Point),
+    Input(Id, usize),
+    Output(Id, usize),
+}
+
+impl<Id> From<Point> for Endpoint<Id> {
+    fn from(point: Point) -> Self {
+        Endpoint::Point(point)
+    }
+}
+
+/// Centers of the port dots of a node, in the scaled space the graph is laid out in.
+#[derive(Debug, Clone, Default)]
+pub struct PortAnchors {
+    pub inputs: Vec<Point>,
+    pub outputs: Vec<Point>,
+}
+
+impl<Id: PartialEq> Endpoint<Id> {
+    pub(crate) fn resolve(&self, context: &LayoutContext<'_, Id>) -> Option<Vector> {
+        let anchors = |id: &Id| {
+            context
+                .ports
+                .iter()
+                .find(|(node, _)| node == id)
+                .map(|(_, anchors)| anchors)
+        };
+
+        let point = match self {
+            Endpoint::Point(point) => return Some(Vector::new(point.x, point.y) * context.scale),
+            Endpoint::Input(id, index) => anchors(id)?.inputs.get(*index)?,
+            Endpoint::Output(id, index) => anchors(id)?.outputs.get(*index)?,
+        };
+
+        Some(Vector::new(point.x, point.y))
+    }
+}
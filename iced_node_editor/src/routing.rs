@@ -0,0 +1,750 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use iced::{Rectangle, Vector};
+
+use crate::node_element::LayoutContext;
+
+/// How a connection travels from its source to its target.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConnectionRouting {
+    /// A Catmull-Rom spline leaving and entering horizontally.
+    #[default]
+    Spline,
+    /// A cubic bezier with horizontal tangents, longer as `strength` grows.
+    Bezier {
+        strength: f32,
+    },
+    Straight,
+    /// Horizontal and vertical segments with corners rounded with quadratic curves
+    /// `radius` graph units long, detouring when the target is behind the source.
+    Orthogonal {
+        radius: f32,
+    },
+    /// Horizontal segments joined by a single vertical step halfway.
+    Step,
+    /// Horizontal and vertical segments going around the nodes of the
+    /// [`GraphContainer`](crate::GraphContainer), with smoothed corners if `smooth`.
+    AroundNodes {
+        smooth: bool,
+    },
+}
+
+/// Paths routed around nodes, kept between layouts so that only the wires whose
+/// surroundings changed are routed again. Store it next to the graph and hand it
+/// to [`GraphContainer::route_cache`](crate::GraphContainer::route_cache).
+#[derive(Debug, Default)]
+pub struct RouteCache {
+    routes: RefCell<HashMap<[u32; 5], CachedRoute>>,
+    /// Number of layouts so far, counted by [`RouteCache::trim`].
+    generation: Cell<u64>,
+}
+
+#[derive(Debug)]
+struct CachedRoute {
+    obstacles: Vec<Rectangle>,
+    path: Vec<Vector>,
+    last_used: u64,
+}
+
+/// Layouts a route may go unused before it is forgotten, enough for several
+/// containers sharing a cache not to evict each other's routes.
+const IDLE_LAYOUTS: u64 = 16;
+
+impl RouteCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&self) {
+        self.routes.borrow_mut().clear();
+    }
+
+    /// Ends a layout, forgetting the routes that were not needed over the last
+    /// [`IDLE_LAYOUTS`] ones.
+    pub(crate) fn trim(&self) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.routes
+            .borrow_mut()
+            .retain(|_, route| generation - route.last_used <= IDLE_LAYOUTS);
+    }
+}
+
+/// Distance an orthogonal route runs straight out of its endpoints, in graph units.
+const ORTHOGONAL_MARGIN: f32 = 20.0;
+
+/// Shortest bezier tangent at a `strength` of 1, in graph units.
+const MIN_TANGENT: f32 = 40.0;
+
+/// Corner radius of unsmoothed routes around nodes, in graph units.
+const AROUND_NODES_RADIUS: f32 = 8.0;
+
+/// Subdivisions every curve gets before flatness is checked, so that
+/// S-shaped spans aren't mistaken for straight ones.
+const MIN_DEPTH: u32 = 2;
+const MAX_DEPTH: u32 = 10;
+
+/// How curves are turned into polylines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Sampling {
+    /// A fixed number of points, evenly spaced in the curve parameter.
+    Uniform(usize),
+    /// As many points as needed for the polyline to stay within `tolerance`
+    /// pixels of the curve.
+    Adaptive { tolerance: f32 },
+}
+
+/// Builds the polyline of a connection between two points already multiplied by the scale.
+pub(crate) fn route<Id>(
+    routing: ConnectionRouting,
+    from: Vector,
+    to: Vector,
+    sampling: Sampling,
+    context: &LayoutContext<'_, Id>,
+) -> Vec<Vector> {
+    let scale = context.scale;
+
+    let mut points = match routing {
+        ConnectionRouting::Spline => generate_spline(from, 1.0, to, sampling, 1.0),
+        ConnectionRouting::Bezier { strength } => {
+            let tangent = ((to.x - from.x).abs() / 2.0).max(MIN_TANGENT * scale) * strength;
+            cubic_bezier(
+                from,
+                from + Vector::new(tangent, 0.0),
+                to - Vector::new(tangent, 0.0),
+                to,
+                sampling,
+            )
+        }
+        ConnectionRouting::Straight => vec![from, to],
+        ConnectionRouting::Orthogonal { radius } => round_corners(
+            &orthogonal(from, to, ORTHOGONAL_MARGIN * scale),
+            radius * scale,
+            sampling,
+        ),
+        ConnectionRouting::Step => {
+            let middle = (from.x + to.x) / 2.0;
+            vec![
+                from,
+                Vector::new(middle, from.y),
+                Vector::new(middle, to.y),
+                to,
+            ]
+        }
+        ConnectionRouting::AroundNodes { smooth } => {
+            let path = route_around(
+                from,
+                to,
+                context.obstacles,
+                ORTHOGONAL_MARGIN * scale,
+                context.route_cache,
+            );
+            let radius = if smooth {
+                f32::MAX
+            } else {
+                AROUND_NODES_RADIUS * scale
+            };
+            round_corners(&path, radius, sampling)
+        }
+    };
+
+    points.dedup_by(|a, b| length(*a - *b) < 0.01);
+    points
+}
+
+/// Routes an orthogonal path leaving `from` to the right and entering `to` from the
+/// left, staying `margin / 2` away from every obstacle.
+fn route_around(
+    from: Vector,
+    to: Vector,
+    obstacles: &[Rectangle],
+    margin: f32,
+    cache: Option<&RouteCache>,
+) -> Vec<Vector> {
+    let start = from + Vector::new(margin, 0.0);
+    let end = to - Vector::new(margin, 0.0);
+    let obstacles = surrounding_obstacles(start, end, obstacles, margin / 2.0);
+
+    let key = [from.x, from.y, to.x, to.y, margin].map(f32::to_bits);
+    if let Some(cache) = cache {
+        if let Some(route) = cache.routes.borrow_mut().get_mut(&key) {
+            if route.obstacles == obstacles {
+                route.last_used = cache.generation.get();
+                return route.path.clone();
+            }
+        }
+    }
+
+    let path = match shortest_path(start, end, &obstacles, margin) {
+        Some(mut path) => {
+            path.insert(0, from);
+            path.push(to);
+            remove_collinear(&path)
+        }
+        None => orthogonal(from, to, margin),
+    };
+
+    if let Some(cache) = cache {
+        cache.routes.borrow_mut().insert(
+            key,
+            CachedRoute {
+                obstacles,
+                path: path.clone(),
+                last_used: cache.generation.get(),
+            },
+        );
+    }
+
+    path
+}
+
+/// Inflates the obstacles that a route between `start` and `end` may have to go
+/// around: those touching their bounding box, grown by every obstacle added.
+fn surrounding_obstacles(
+    start: Vector,
+    end: Vector,
+    obstacles: &[Rectangle],
+    padding: f32,
+) -> Vec<Rectangle> {
+    let inflated: Vec<Rectangle> = obstacles
+        .iter()
+        .map(|obstacle| Rectangle {
+            x: obstacle.x - padding,
+            y: obstacle.y - padding,
+            width: obstacle.width + padding * 2.0,
+            height: obstacle.height + padding * 2.0,
+        })
+        .collect();
+
+    let mut region = Rectangle {
+        x: start.x.min(end.x),
+        y: start.y.min(end.y),
+        width: (start.x - end.x).abs(),
+        height: (start.y - end.y).abs(),
+    };
+    let mut included = vec![false; inflated.len()];
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (obstacle, included) in inflated.iter().zip(&mut included) {
+            if !*included && touches(obstacle, &region) {
+                *included = true;
+                region = region.union(obstacle);
+                changed = true;
+            }
+        }
+    }
+
+    inflated
+        .into_iter()
+        .zip(included)
+        .filter_map(|(obstacle, included)| included.then_some(obstacle))
+        .collect()
+}
+
+fn touches(a: &Rectangle, b: &Rectangle) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width && a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
+fn inside(obstacles: &[Rectangle], point: Vector) -> bool {
+    obstacles.iter().any(|obstacle| {
+        point.x > obstacle.x
+            && point.x < obstacle.x + obstacle.width
+            && point.y > obstacle.y
+            && point.y < obstacle.y + obstacle.height
+    })
+}
+
+#[derive(PartialEq)]
+struct Candidate {
+    cost: f32,
+    state: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* search over the grid formed by the obstacle edges and both endpoints,
+/// penalizing every bend by `bend_cost`.
+fn shortest_path(
+    start: Vector,
+    end: Vector,
+    obstacles: &[Rectangle],
+    bend_cost: f32,
+) -> Option<Vec<Vector>> {
+    let coordinates = |start: f32, end: f32, edges: &dyn Fn(&Rectangle) -> [f32; 2]| {
+        let mut values: Vec<f32> = obstacles.iter().flat_map(edges).collect();
+        values.extend([start, end]);
+        values.sort_by(f32::total_cmp);
+        values.dedup();
+        values
+    };
+    let xs = coordinates(start.x, end.x, &|r| [r.x, r.x + r.width]);
+    let ys = coordinates(start.y, end.y, &|r| [r.y, r.y + r.height]);
+
+    let position = |x: &[f32], value: f32| x.iter().position(|v| *v == value);
+    let (start_x, start_y) = (position(&xs, start.x)?, position(&ys, start.y)?);
+    let (end_x, end_y) = (position(&xs, end.x)?, position(&ys, end.y)?);
+
+    let point = |i: usize, j: usize| Vector::new(xs[i], ys[j]);
+    let heuristic = |i: usize, j: usize| (xs[i] - end.x).abs() + (ys[j] - end.y).abs();
+
+    // States are grid points paired with the direction they were entered in:
+    // 0 right, 1 left, 2 down, 3 up.
+    let width = xs.len();
+    let states = width * ys.len() * 4;
+    let mut costs = vec![f32::INFINITY; states];
+    let mut previous = vec![usize::MAX; states];
+    let mut queue = BinaryHeap::new();
+
+    let first = (start_y * width + start_x) * 4;
+    costs[first] = 0.0;
+    queue.push(Candidate {
+        cost: heuristic(start_x, start_y),
+        state: first,
+    });
+
+    while let Some(Candidate { state, .. }) = queue.pop() {
+        let (cell, direction) = (state / 4, state % 4);
+        let (i, j) = (cell % width, cell / width);
+
+        if (i, j) == (end_x, end_y) {
+            let mut path = Vec::new();
+            let mut state = state;
+            while state != usize::MAX {
+                let cell = state / 4;
+                path.push(point(cell % width, cell / width));
+                state = previous[state];
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let neighbors = [
+            (i + 1 < width).then(|| (i + 1, j)),
+            i.checked_sub(1).map(|i| (i, j)),
+            (j + 1 < ys.len()).then(|| (i, j + 1)),
+            j.checked_sub(1).map(|j| (i, j)),
+        ];
+
+        for (next_direction, neighbor) in neighbors.into_iter().enumerate() {
+            let Some((x, y)) = neighbor else {
+                continue;
+            };
+
+            let from = point(i, j);
+            let to = point(x, y);
+            let is_endpoint = (x, y) == (end_x, end_y);
+            if (!is_endpoint && inside(obstacles, to)) || inside(obstacles, (from + to) * 0.5) {
+                continue;
+            }
+
+            let mut cost = costs[state] + length(to - from);
+            if next_direction != direction {
+                cost += bend_cost;
+            }
+            // Entering the target from any side but the left needs one more bend.
+            if is_endpoint && next_direction != 0 {
+                cost += bend_cost;
+            }
+
+            let next = (y * width + x) * 4 + next_direction;
+            if cost < costs[next] {
+                costs[next] = cost;
+                previous[next] = state;
+                queue.push(Candidate {
+                    cost: cost + heuristic(x, y),
+                    state: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+fn remove_collinear(points: &[Vector]) -> Vec<Vector> {
+    let mut result: Vec<Vector> = Vec::new();
+
+    for point in points {
+        if result
+            .last()
+            .is_some_and(|last| length(*last - *point) < 0.01)
+        {
+            continue;
+        }
+
+        if result.len() >= 2 {
+            let a = result[result.len() - 2];
+            let b = result[result.len() - 1];
+            let cross = (b.x - a.x) * (point.y - b.y) - (b.y - a.y) * (point.x - b.x);
+            if cross.abs() < 0.01 {
+                result.pop();
+            }
+        }
+
+        result.push(*point);
+    }
+
+    result
+}
+
+fn orthogonal(from: Vector, to: Vector, margin: f32) -> Vec<Vector> {
+    if to.x - from.x >= margin * 2.0 {
+        let middle = (from.x + to.x) / 2.0;
+        return vec![
+            from,
+            Vector::new(middle, from.y),
+            Vector::new(middle, to.y),
+            to,
+        ];
+    }
+
+    let middle = (from.y + to.y) / 2.0;
+    vec![
+        from,
+        Vector::new(from.x + margin, from.y),
+        Vector::new(from.x + margin, middle),
+        Vector::new(to.x - margin, middle),
+        Vector::new(to.x - margin, to.y),
+        to,
+    ]
+}
+
+/// Replaces every inner corner of `points` by a curve cutting `radius` off both of its sides.
+pub(crate) fn round_corners(points: &[Vector], radius: f32, sampling: Sampling) -> Vec<Vector> {
+    if points.len() < 3 || radius <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut result = vec![points[0]];
+    for corner in points.windows(3) {
+        let (previous, point, next) = (corner[0], corner[1], corner[2]);
+        let radius = radius
+            .min(length(previous - point) / 2.0)
+            .min(length(next - point) / 2.0);
+
+        let start = point + normalize(previous - point) * radius;
+        let end = point + normalize(next - point) * radius;
+        result.extend(sample(
+            |t| start * ((1.0 - t) * (1.0 - t)) + point * (2.0 * t * (1.0 - t)) + end * (t * t),
+            sampling,
+        ));
+    }
+    result.push(points[points.len() - 1]);
+
+    result
+}
+
+fn cubic_bezier(p0: Vector, p1: Vector, p2: Vector, p3: Vector, sampling: Sampling) -> Vec<Vector> {
+    sample(
+        |t| {
+            let u = 1.0 - t;
+            p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+        },
+        sampling,
+    )
+}
+
+/// Evaluates `curve` over `0.0..=1.0` into a polyline.
+fn sample(curve: impl Fn(f32) -> Vector, sampling: Sampling) -> Vec<Vector> {
+    match sampling {
+        Sampling::Uniform(number_of_segments) => {
+            let number_of_segments = number_of_segments.max(2);
+            (0..number_of_segments)
+                .map(|i| curve(i as f32 / (number_of_segments - 1) as f32))
+                .collect()
+        }
+        Sampling::Adaptive { tolerance } => {
+            let (start, end) = (curve(0.0), curve(1.0));
+            let mut points = vec![start];
+            subdivide(&curve, (0.0, start), (1.0, end), tolerance, 0, &mut points);
+            points
+        }
+    }
+}
+
+/// Splits the span between `start` and `end` in two until its midpoint lies
+/// within `tolerance` of the chord, pushing every point but `start`.
+fn subdivide(
+    curve: &impl Fn(f32) -> Vector,
+    start: (f32, Vector),
+    end: (f32, Vector),
+    tolerance: f32,
+    depth: u32,
+    points: &mut Vec<Vector>,
+) {
+    let t = (start.0 + end.0) / 2.0;
+    let middle = (t, curve(t));
+
+    if depth < MAX_DEPTH
+        && (depth < MIN_DEPTH || distance_to_chord(middle.1, start.1, end.1) > tolerance)
+    {
+        subdivide(curve, start, middle, tolerance, depth + 1, points);
+        subdivide(curve, middle, end, tolerance, depth + 1, points);
+    } else {
+        points.push(end.1);
+    }
+}
+
+fn distance_to_chord(point: Vector, start: Vector, end: Vector) -> f32 {
+    let chord = end - start;
+    let chord_length = length(chord);
+    if chord_length == 0.0 {
+        return length(point - start);
+    }
+
+    ((point.x - start.x) * chord.y - (point.y - start.y) * chord.x).abs() / chord_length
+}
+
+fn length(vector: Vector) -> f32 {
+    dot_vector(vector, vector).sqrt()
+}
+
+fn normalize(vector: Vector) -> Vector {
+    let length = length(vector);
+    if length == 0.0 {
+        vector
+    } else {
+        vector * (1.0 / length)
+    }
+}
+
+fn dot_vector(vector: Vector, other: Vector) -> f32 {
+    vector.x * other.x + vector.y * other.y
+}
+
+fn generate_spline(
+    from: Vector,
+    control_scale: f32,
+    to: Vector,
+    sampling: Sampling,
+    alpha: f32,
+) -> Vec<Vector> {
+    sample(
+        |t| {
+            catmull_rom(
+                Vector::new(from.x - control_scale, from.y),
+                from,
+                to,
+                Vector::new(to.x + control_scale, to.y),
+                t,
+                alpha,
+            )
+        },
+        sampling,
+    )
+}
+
+// Code taken and adapted from https://en.wikipedia.org/wiki/Centripetal_Catmull%E2%80%93Rom_spline
+fn get_t(t: f32, alpha: f32, p0: Vector, p1: Vector) -> f32 {
+    let d = p1 - p0;
+    let a = dot_vector(d, d);
+    let b = a.powf(alpha * 0.5);
+    b + t
+}
+
+fn catmull_rom(p0: Vector, p1: Vector, p2: Vector, p3: Vector, t: f32, alpha: f32) -> Vector {
+    let t0 = 0.0;
+    let t1 = get_t(t0, alpha, p0, p1);
+    let t2 = get_t(t1, alpha, p1, p2);
+    let t3 = get_t(t2, alpha, p2, p3);
+    // Coincident endpoints leave nothing to interpolate but would divide by zero.
+    if t2 == t1 {
+        return p1;
+    }
+    let t = t1 + (t2 - t1) * t;
+    let a1 = p0 * ((t1 - t) / (t1 - t0)) + p1 * ((t - t0) / (t1 - t0));
+    let a2 = p1 * ((t2 - t) / (t2 - t1)) + p2 * ((t - t1) / (t2 - t1));
+    let a3 = p2 * ((t3 - t) / (t3 - t2)) + p3 * ((t - t2) / (t3 - t2));
+    let b1 = a1 * ((t2 - t) / (t2 - t0)) + a2 * ((t - t0) / (t2 - t0));
+    let b2 = a2 * ((t3 - t) / (t3 - t1)) + a3 * ((t - t1) / (t3 - t1));
+    b1 * ((t2 - t) / (t2 - t1)) + b2 * ((t - t1) / (t2 - t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crosses(obstacle: &Rectangle, a: Vector, b: Vector) -> bool {
+        (0..=20).any(|step| inside(&[*obstacle], a + (b - a) * (step as f32 / 20.0)))
+    }
+
+    #[test]
+    fn shortest_path_detours_around_obstacles() {
+        let start = Vector::new(0.0, 0.0);
+        let end = Vector::new(100.0, 0.0);
+        let obstacle = Rectangle::new(iced::Point::new(40.0, -20.0), iced::Size::new(20.0, 40.0));
+
+        let path = shortest_path(start, end, &[obstacle], 10.0).unwrap();
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for pair in path.windows(2) {
+            assert!(pair[0].x == pair[1].x || pair[0].y == pair[1].y);
+            assert!(!crosses(&obstacle, pair[0], pair[1]));
+        }
+
+        // Around the top or bottom edge, with the fewest bends.
+        let path = remove_collinear(&path);
+        assert_eq!(path.len(), 5, "{:?}", path);
+        assert!(path
+            .iter()
+            .all(|point| point.y == path[2].y || point.y == 0.0));
+        assert_eq!(path[2].y.abs(), 20.0);
+    }
+
+    #[test]
+    fn unreachable_endpoints_fall_back_to_an_orthogonal_route() {
+        let start = Vector::new(0.0, 0.0);
+        let end = Vector::new(100.0, 0.0);
+        let walled_in = Rectangle::new(iced::Point::new(80.0, -20.0), iced::Size::new(40.0, 40.0));
+
+        assert_eq!(shortest_path(start, end, &[walled_in], 10.0), None);
+
+        let from = start - Vector::new(20.0, 0.0);
+        let to = end + Vector::new(20.0, 0.0);
+        assert_eq!(
+            route_around(from, to, &[walled_in], 20.0, None),
+            orthogonal(from, to, 20.0)
+        );
+    }
+
+    #[test]
+    fn round_corners_cut_every_corner() {
+        let points = [
+            Vector::new(0.0, 0.0),
+            Vector::new(100.0, 0.0),
+            Vector::new(100.0, 6.0),
+            Vector::new(200.0, 6.0),
+        ];
+        let rounded = round_corners(&points, 10.0, Sampling::Uniform(5));
+
+        assert_eq!(rounded.first(), Some(&points[0]));
+        assert_eq!(rounded.last(), Some(&points[3]));
+        assert!(!rounded.contains(&points[1]) && !rounded.contains(&points[2]));
+
+        // The radius shrinks to half of the short side between both corners.
+        assert_eq!(rounded[1], Vector::new(97.0, 0.0));
+        assert_eq!(rounded[5], Vector::new(100.0, 3.0));
+        assert_eq!(rounded[6], Vector::new(100.0, 3.0));
+        assert_eq!(rounded[10], Vector::new(103.0, 6.0));
+
+        for point in &rounded[1..rounded.len() - 1] {
+            assert!(point.x >= 97.0 && point.x <= 103.0 && point.y >= 0.0 && point.y <= 6.0);
+        }
+
+        assert_eq!(
+            round_corners(&points[..2], 10.0, Sampling::Uniform(5)),
+            &points[..2]
+        );
+        assert_eq!(round_corners(&points, 0.0, Sampling::Uniform(5)), points);
+    }
+
+    #[test]
+    fn containers_sharing_a_cache_keep_each_others_routes() {
+        let cache = RouteCache::new();
+        let first = (Vector::new(0.0, 0.0), Vector::new(100.0, 0.0));
+        let second = (Vector::new(0.0, 50.0), Vector::new(100.0, 80.0));
+
+        // Two containers, each routing its own wire and trimming the cache.
+        for _ in 0..IDLE_LAYOUTS * 2 {
+            route_around(first.0, first.1, &[], 20.0, Some(&cache));
+            cache.trim();
+            route_around(second.0, second.1, &[], 20.0, Some(&cache));
+            cache.trim();
+        }
+        assert_eq!(cache.routes.borrow().len(), 2);
+
+        for _ in 0..IDLE_LAYOUTS {
+            route_around(first.0, first.1, &[], 20.0, Some(&cache));
+            cache.trim();
+        }
+        assert_eq!(cache.routes.borrow().len(), 1);
+    }
+
+    fn distance_to_polyline(point: Vector, polyline: &[Vector]) -> f32 {
+        polyline
+            .windows(2)
+            .map(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                let chord = b - a;
+                let t = (dot_vector(point - a, chord) / dot_vector(chord, chord).max(f32::EPSILON))
+                    .clamp(0.0, 1.0);
+                length(point - (a + chord * t))
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn adaptive_sampling_stays_within_tolerance() {
+        let curve = |t: f32| {
+            let u = 1.0 - t;
+            let (p0, p1, p2, p3) = (
+                Vector::new(0.0, 0.0),
+                Vector::new(300.0, 0.0),
+                Vector::new(-200.0, 200.0),
+                Vector::new(100.0, 200.0),
+            );
+            p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+        };
+
+        for tolerance in [0.1, 0.5, 2.0] {
+            let points = sample(curve, Sampling::Adaptive { tolerance });
+
+            assert!(points.len() <= (1 << MAX_DEPTH) + 1);
+            for step in 0..=1000 {
+                let point = curve(step as f32 / 1000.0);
+                let error = distance_to_polyline(point, &points);
+                assert!(error <= tolerance * 1.5, "{} > {}", error, tolerance);
+            }
+        }
+
+        // A looser tolerance needs fewer points.
+        let fine = sample(curve, Sampling::Adaptive { tolerance: 0.1 });
+        let coarse = sample(curve, Sampling::Adaptive { tolerance: 2.0 });
+        assert!(coarse.len() < fine.len());
+    }
+
+    #[test]
+    fn adaptive_sampling_ends_on_degenerate_curves() {
+        let point = Vector::new(10.0, 20.0);
+        let tolerance = Sampling::Adaptive { tolerance: 0.01 };
+
+        let points = sample(|_| point, tolerance);
+        assert_eq!(points.len(), (1 << MIN_DEPTH) + 1);
+        assert!(points.iter().all(|p| *p == point));
+
+        // Curves that come back to their start have a zero length chord.
+        let circle = |t: f32| {
+            let (sin, cos) = (t * std::f32::consts::TAU).sin_cos();
+            point + Vector::new(sin, 1.0 - cos) * 50.0
+        };
+        let points = sample(circle, tolerance);
+        assert!(points.len() > (1 << MIN_DEPTH) + 1);
+        assert!(points.len() <= (1 << MAX_DEPTH) + 1);
+
+        // Wires between two ports at the same place.
+        for sampling in [tolerance, Sampling::Uniform(8)] {
+            let spline = generate_spline(point, 1.0, point, sampling, 1.0);
+            assert!(spline.iter().all(|p| length(*p - point) < 1e-3));
+
+            let bezier = cubic_bezier(point, point, point, point, sampling);
+            assert!(bezier.iter().all(|p| length(*p - point) < 1e-3));
+        }
+    }
+}
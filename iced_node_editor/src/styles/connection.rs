@@ -1,21 +1,37 @@
 use iced::{Color, Theme};
 
-#[derive(Debug, Clone, Copy)]
-pub struct Appearance {
-    pub color: Option<Color>,
+/// How a wire is stroked along its length. Lengths are in graph units, so
+/// patterns scale with the zoom level and stay anchored to the wire's start.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum StrokePattern {
+    #[default]
+    Solid,
+    /// Alternating dash and gap lengths, starting with a dash.
+    Dashed(Vec<f32>),
+    /// Round dots as wide as the wire, placed at the given spacing.
+    Dotted(f32),
 }
 
-impl std::default::Default for Appearance {
-    fn default() -> Self {
-        Self {
-            color: None
-        }
-    }
+#[derive(Debug, Clone, Default)]
+pub struct Appearance {
+    pub color: Option<Color>,
+    /// Color of the start and end markers, defaulting to `color`.
+    pub marker_color: Option<Color>,
+    pub pattern: StrokePattern,
+    /// Halo drawn behind the stroke, `outline_width` wide on each side.
+    pub outline_color: Option<Color>,
+    pub outline_width: f32,
+    /// Color of the pulses of an animated [`Flow`](crate::Flow), defaulting to `color`.
+    pub flow_color: Option<Color>,
 }
 
 pub trait StyleSheet {
     type Style: Default;
     fn appearance(&self, style: &Self::Style) -> Appearance;
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
 }
 
 #[derive(Default)]
@@ -34,8 +50,26 @@ impl StyleSheet for Theme {
         match style {
             Node::Default => Appearance {
                 color: Some(palette.primary.base.color),
+                marker_color: None,
+                pattern: StrokePattern::Solid,
+                outline_color: None,
+                outline_width: 0.0,
+                flow_color: Some(palette.primary.strong.color),
             },
             Node::Custom(custom) => custom.appearance(self),
         }
     }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => Appearance {
+                outline_color: Some(palette.primary.weak.color),
+                outline_width: 2.0,
+                ..self.appearance(style)
+            },
+            Node::Custom(custom) => custom.hovered(self),
+        }
+    }
 }
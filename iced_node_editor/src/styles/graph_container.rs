@@ -1,7 +1,7 @@
 use iced::{Background, Color, Theme};
 use palette::{Darken, FromColor, Hsl, Lighten, Srgb};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Appearance {
     pub background: Option<Background>,
     pub minor_guidelines_color: Option<Color>,
@@ -10,20 +10,10 @@ pub struct Appearance {
     pub minor_guidelines_spacing: Option<f32>,
     pub mid_guidelines_spacing: Option<f32>,
     pub major_guidelines_spacing: Option<f32>,
-}
-
-impl std::default::Default for Appearance {
-    fn default() -> Self {
-        Self {
-            background: None,
-            minor_guidelines_color: None,
-            mid_guidelines_color: None,
-            major_guidelines_color: None,
-            minor_guidelines_spacing: None,
-            mid_guidelines_spacing: None,
-            major_guidelines_spacing: None,
-        }
-    }
+    /// Box that tooltips of nodes, ports and connections are shown in.
+    pub tooltip_background: Option<Background>,
+    pub tooltip_text_color: Option<Color>,
+    pub tooltip_border_color: Option<Color>,
 }
 
 pub trait StyleSheet {
@@ -74,6 +64,9 @@ impl StyleSheet for Theme {
                 minor_guidelines_spacing: Some(10.0),
                 mid_guidelines_spacing: Some(50.0),
                 major_guidelines_spacing: Some(100.0),
+                tooltip_background: Some(Background::Color(palette.background.weak.color)),
+                tooltip_text_color: Some(palette.background.weak.text),
+                tooltip_border_color: Some(palette.background.strong.color),
             },
             GraphContainer::Custom(custom) => custom.appearance(self),
         }
@@ -1,4 +1,6 @@
-use iced::{Background, Color, Theme};
+use iced::{Background, Color, Theme, Vector};
+
+use crate::Status;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Appearance {
@@ -7,6 +9,19 @@ pub struct Appearance {
     pub border_radius: f32,
     pub border_width: f32,
     pub border_color: Color,
+    /// Fill of the title bar of nodes with a title or an icon.
+    pub header_background: Option<Background>,
+    pub header_text_color: Option<Color>,
+    /// Color of the port dots, defaulting to `border_color`.
+    pub port_color: Option<Color>,
+    /// Shadow cast beneath the node, `shadow_offset` away and blurred over `shadow_blur`.
+    /// Lengths are in graph units, so they scale with the zoom level.
+    pub shadow_color: Option<Color>,
+    pub shadow_offset: Vector,
+    pub shadow_blur: f32,
+    /// Halo around the node fading out over `glow_width`, in graph units.
+    pub glow_color: Option<Color>,
+    pub glow_width: f32,
 }
 
 impl std::default::Default for Appearance {
@@ -17,6 +32,14 @@ impl std::default::Default for Appearance {
             border_radius: 0.0,
             border_width: 0.0,
             border_color: Color::TRANSPARENT,
+            header_background: None,
+            header_text_color: None,
+            port_color: None,
+            shadow_color: None,
+            shadow_offset: Vector::new(0.0, 0.0),
+            shadow_blur: 0.0,
+            glow_color: None,
+            glow_width: 0.0,
         }
     }
 }
@@ -24,6 +47,32 @@ impl std::default::Default for Appearance {
 pub trait StyleSheet {
     type Style: Default;
     fn appearance(&self, style: &Self::Style) -> Appearance;
+
+    fn selected(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
+
+    /// Laid over muted and bypassed nodes to fade them out along with their contents,
+    /// usually the graph background made partly transparent. With `None`, only the
+    /// colors of the node itself are faded.
+    fn muted_veil(&self, _style: &Self::Style) -> Option<Color> {
+        None
+    }
+
+    /// Color of the status badge and of the border, or `None` to show neither.
+    fn status_color(&self, _style: &Self::Style, status: Status) -> Option<Color> {
+        match status {
+            Status::Idle => None,
+            Status::Running => Some(Color::from_rgb(0.25, 0.55, 0.95)),
+            Status::Success => Some(Color::from_rgb(0.3, 0.7, 0.35)),
+            Status::Warning => Some(Color::from_rgb(0.95, 0.7, 0.15)),
+            Status::Error => Some(Color::from_rgb(0.9, 0.25, 0.25)),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -46,8 +95,78 @@ impl StyleSheet for Theme {
                 border_radius: 5.0,
                 border_width: 1.0,
                 text_color: Some(palette.primary.base.color),
+                header_background: Some(Background::Color(palette.primary.weak.color)),
+                header_text_color: Some(palette.primary.weak.text),
+                port_color: Some(palette.primary.strong.color),
+                shadow_color: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.35)),
+                shadow_offset: Vector::new(0.0, 3.0),
+                shadow_blur: 8.0,
+                glow_color: None,
+                glow_width: 0.0,
             },
             Node::Custom(custom) => custom.appearance(self),
         }
     }
+
+    fn selected(&self, style: &Self::Style) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => Appearance {
+                border_color: palette.primary.strong.color,
+                border_width: 2.0,
+                glow_color: Some(Color {
+                    a: 0.5,
+                    ..palette.primary.strong.color
+                }),
+                glow_width: 6.0,
+                ..self.appearance(style)
+            },
+            Node::Custom(custom) => custom.selected(self),
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => Appearance {
+                glow_color: Some(Color {
+                    a: 0.35,
+                    ..palette.primary.weak.color
+                }),
+                glow_width: 4.0,
+                ..self.appearance(style)
+            },
+            Node::Custom(custom) => custom.hovered(self),
+        }
+    }
+
+    fn status_color(&self, style: &Self::Style, status: Status) -> Option<Color> {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => match status {
+                Status::Idle => None,
+                Status::Running => Some(palette.primary.strong.color),
+                Status::Success => Some(palette.success.base.color),
+                // The palette has no warning color.
+                Status::Warning => Some(Color::from_rgb(0.95, 0.7, 0.15)),
+                Status::Error => Some(palette.danger.base.color),
+            },
+            Node::Custom(custom) => custom.status_color(self, status),
+        }
+    }
+
+    fn muted_veil(&self, style: &Self::Style) -> Option<Color> {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => Some(Color {
+                a: 0.55,
+                ..palette.background.base.color
+            }),
+            Node::Custom(custom) => custom.muted_veil(self),
+        }
+    }
 }
@@ -0,0 +1,444 @@
+use std::f32::consts::PI;
+
+use iced::Vector;
+
+/// How the segments of a wire are joined where it bends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How the ends of a wire, and of each of its dashes, are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+/// Longest miter allowed, relative to half the line width, before falling back to a bevel.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Largest angle covered by a single triangle of a round join or cap.
+const ROUND_STEP: f32 = PI / 12.0;
+
+/// Width of the edge over which lines fade out, in pixels.
+pub(crate) const FEATHER: f32 = 1.0;
+
+/// Triangles with a coverage per vertex, by which the color alpha is multiplied
+/// to get anti-aliased edges, and the arc length along the stroked path at which
+/// every vertex lies, to color it with a gradient.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tessellation {
+    pub(crate) vertices: Vec<Vector>,
+    pub(crate) coverage: Vec<f32>,
+    pub(crate) distances: Vec<f32>,
+    pub(crate) indices: Vec<u32>,
+}
+
+/// The parameters of a stroke, with widths in pixels.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pen {
+    pub(crate) half_width: f32,
+    pub(crate) join: LineJoin,
+    pub(crate) cap: LineCap,
+    pub(crate) feather: f32,
+}
+
+/// A cross-section of a stroke: its center and the offsets of its left and
+/// right edges for a half width of 1.
+struct Section {
+    center: Vector,
+    distance: f32,
+    left: Vector,
+    right: Vector,
+}
+
+impl Tessellation {
+    /// Fully covered triangles.
+    pub(crate) fn polygon(vertices: Vec<Vector>, indices: Vec<u32>) -> Self {
+        Tessellation {
+            coverage: vec![1.0; vertices.len()],
+            distances: vec![0.0; vertices.len()],
+            vertices,
+            indices,
+        }
+    }
+
+    pub(crate) fn append(&mut self, other: Tessellation) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(other.vertices);
+        self.coverage.extend(other.coverage);
+        self.distances.extend(other.distances);
+        self.indices
+            .extend(other.indices.iter().map(|i| i + offset));
+    }
+
+    /// Moves every vertex `distance` further along the stroked path.
+    pub(crate) fn offset_along(mut self, distance: f32) -> Self {
+        self.distances.iter_mut().for_each(|d| *d += distance);
+        self
+    }
+
+    fn push(&mut self, position: Vector, coverage: f32, distance: f32) -> u32 {
+        self.vertices.push(position);
+        self.coverage.push(coverage);
+        self.distances.push(distance);
+        self.vertices.len() as u32 - 1
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend([a, b, c, a, c, d]);
+    }
+}
+
+impl Pen {
+    /// Half widths of the solid core and of the faded edge, and the coverage of the core,
+    /// which drops for lines thinner than the feather.
+    fn extents(&self) -> (f32, f32, f32) {
+        if self.feather <= 0.0 {
+            return (self.half_width, self.half_width, 1.0);
+        }
+
+        let core = (self.half_width - self.feather / 2.0).max(0.0);
+        let outer = self.half_width + self.feather / 2.0;
+        let coverage = (self.half_width * 2.0 / self.feather).min(1.0);
+        (core, outer, coverage)
+    }
+}
+
+/// Tessellates a polyline as one continuous strip, so consecutive segments share
+/// their edges instead of overlapping or leaving notches.
+pub(crate) fn stroke_polyline(points: &[Vector], pen: &Pen) -> Tessellation {
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| length(*a - *b) < 1e-3);
+
+    if points.len() < 2 {
+        return match (points.first(), pen.cap) {
+            (Some(center), LineCap::Round) => disc(*center, pen),
+            _ => Tessellation::default(),
+        };
+    }
+
+    let directions: Vec<Vector> = points
+        .windows(2)
+        .map(|pair| normalize(pair[1] - pair[0]))
+        .collect();
+
+    let distances: Vec<f32> = std::iter::once(0.0)
+        .chain(points.windows(2).scan(0.0, |travelled, pair| {
+            *travelled += length(pair[1] - pair[0]);
+            Some(*travelled)
+        }))
+        .collect();
+
+    let first = directions[0];
+    let last = directions[directions.len() - 1];
+    let start = points[0];
+    let end = points[points.len() - 1];
+    let total = distances[distances.len() - 1];
+
+    let mut sections = vec![Section {
+        center: match pen.cap {
+            LineCap::Square => start - first * pen.half_width,
+            _ => start,
+        },
+        distance: 0.0,
+        left: normal(first),
+        right: normal(first) * -1.0,
+    }];
+
+    for (i, point) in points.iter().enumerate().skip(1).take(points.len() - 2) {
+        join(
+            &mut sections,
+            *point,
+            distances[i],
+            directions[i - 1],
+            directions[i],
+            pen.join,
+        );
+    }
+
+    sections.push(Section {
+        center: match pen.cap {
+            LineCap::Square => end + last * pen.half_width,
+            _ => end,
+        },
+        distance: total,
+        left: normal(last),
+        right: normal(last) * -1.0,
+    });
+
+    let mut tessellation = strip(&sections, pen);
+
+    if pen.cap == LineCap::Round {
+        tessellation.append(fan(start, normal(first), PI, pen));
+        tessellation.append(fan(end, normal(last) * -1.0, PI, pen).offset_along(total));
+    }
+
+    tessellation
+}
+
+/// A round dot as wide as the pen.
+pub(crate) fn disc(center: Vector, pen: &Pen) -> Tessellation {
+    fan(center, Vector::new(1.0, 0.0), 2.0 * PI, pen)
+}
+
+fn join(
+    sections: &mut Vec<Section>,
+    point: Vector,
+    distance: f32,
+    previous: Vector,
+    next: Vector,
+    join: LineJoin,
+) {
+    let (previous_normal, next_normal) = (normal(previous), normal(next));
+    let cross = previous.x * next.y - previous.y * next.x;
+    let miter = normalize(previous_normal + next_normal);
+    let cosine = dot(miter, previous_normal);
+
+    let miter_length = if cosine > 1e-3 {
+        1.0 / cosine
+    } else {
+        f32::INFINITY
+    };
+
+    if cross.abs() < 1e-3 && dot(previous, next) > 0.0
+        || join == LineJoin::Miter && miter_length <= MITER_LIMIT
+    {
+        sections.push(Section {
+            center: point,
+            distance,
+            left: miter * miter_length,
+            right: miter * -miter_length,
+        });
+        return;
+    }
+
+    // The inner edge keeps a single, shortened miter point while the outer
+    // edge turns around the corner.
+    let inner = miter * miter_length.min(MITER_LIMIT);
+    let left_is_outer = dot(previous_normal, next) < 0.0;
+    let angle = dot(previous_normal, next_normal).clamp(-1.0, 1.0).acos();
+    let steps = match join {
+        LineJoin::Round => (angle / ROUND_STEP).ceil().max(1.0) as usize,
+        _ => 1,
+    };
+
+    for step in 0..=steps {
+        let outer = rotate(
+            previous_normal,
+            angle * cross.signum() * step as f32 / steps as f32,
+        );
+
+        sections.push(if left_is_outer {
+            Section {
+                center: point,
+                distance,
+                left: outer,
+                right: inner * -1.0,
+            }
+        } else {
+            Section {
+                center: point,
+                distance,
+                left: inner,
+                right: outer * -1.0,
+            }
+        });
+    }
+}
+
+fn strip(sections: &[Section], pen: &Pen) -> Tessellation {
+    let (core, outer, coverage) = pen.extents();
+    let feathered = outer > core;
+    let mut tessellation = Tessellation::default();
+    let mut previous: Option<[u32; 4]> = None;
+
+    for section in sections {
+        let (center, distance) = (section.center, section.distance);
+        let current = [
+            tessellation.push(center + section.left * outer, 0.0, distance),
+            tessellation.push(center + section.left * core, coverage, distance),
+            tessellation.push(center + section.right * core, coverage, distance),
+            tessellation.push(center + section.right * outer, 0.0, distance),
+        ];
+
+        if let Some(previous) = previous {
+            tessellation.quad(previous[1], current[1], current[2], previous[2]);
+            if feathered {
+                tessellation.quad(previous[0], current[0], current[1], previous[1]);
+                tessellation.quad(previous[2], current[2], current[3], previous[3]);
+            }
+        }
+
+        previous = Some(current);
+    }
+
+    tessellation
+}
+
+/// A circular sector of `sweep` radians around `center`, starting at `from`.
+fn fan(center: Vector, from: Vector, sweep: f32, pen: &Pen) -> Tessellation {
+    let (core, outer, coverage) = pen.extents();
+    let steps = (sweep / ROUND_STEP).ceil().max(1.0) as usize;
+
+    let mut tessellation = Tessellation::default();
+    let middle = tessellation.push(center, coverage, 0.0);
+    let mut previous: Option<(u32, u32)> = None;
+
+    for step in 0..=steps {
+        let direction = rotate(from, sweep * step as f32 / steps as f32);
+        let current = (
+            tessellation.push(center + direction * core, coverage, 0.0),
+            tessellation.push(center + direction * outer, 0.0, 0.0),
+        );
+
+        if let Some(previous) = previous {
+            tessellation.indices.extend([middle, previous.0, current.0]);
+            if outer > core {
+                tessellation.quad(previous.0, previous.1, current.1, current.0);
+            }
+        }
+
+        previous = Some(current);
+    }
+
+    tessellation
+}
+
+fn normal(direction: Vector) -> Vector {
+    Vector::new(-direction.y, direction.x)
+}
+
+fn rotate(vector: Vector, angle: f32) -> Vector {
+    let (sin, cos) = angle.sin_cos();
+    Vector::new(
+        vector.x * cos - vector.y * sin,
+        vector.x * sin + vector.y * cos,
+    )
+}
+
+fn dot(a: Vector, b: Vector) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+fn length(vector: Vector) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+fn normalize(vector: Vector) -> Vector {
+    let length = length(vector);
+    if length == 0.0 {
+        vector
+    } else {
+        vector * (1.0 / length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pen(half_width: f32, join: LineJoin, cap: LineCap) -> Pen {
+        Pen {
+            half_width,
+            join,
+            cap,
+            feather: FEATHER,
+        }
+    }
+
+    fn farthest(tessellation: &Tessellation, from: Vector) -> f32 {
+        tessellation
+            .vertices
+            .iter()
+            .map(|vertex| length(*vertex - from))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn reversals_fall_back_to_a_bevel() {
+        let corner = Vector::new(10.0, 0.0);
+        let points = [Vector::new(0.0, 0.0), corner, Vector::new(0.0, 0.0)];
+        let pen = pen(2.0, LineJoin::Miter, LineCap::Butt);
+
+        let tessellation = stroke_polyline(&points, &pen);
+
+        assert!(tessellation
+            .vertices
+            .iter()
+            .all(|vertex| vertex.x.is_finite() && vertex.y.is_finite()));
+        // A miter would be infinitely long; the bevel stays within the pen.
+        let reach = tessellation
+            .vertices
+            .iter()
+            .map(|vertex| vertex.x)
+            .fold(f32::MIN, f32::max);
+        assert!(reach <= corner.x + pen.half_width + pen.feather);
+        // Both ends, plus the two sections of the bevel.
+        assert_eq!(tessellation.vertices.len(), 4 * 4);
+    }
+
+    #[test]
+    fn duplicate_points_are_ignored() {
+        let pen = pen(2.0, LineJoin::Round, LineCap::Butt);
+        let (a, b) = (Vector::new(0.0, 0.0), Vector::new(10.0, 5.0));
+
+        let clean = stroke_polyline(&[a, b], &pen);
+        let duplicated = stroke_polyline(&[a, a, b, b, b], &pen);
+
+        assert_eq!(duplicated.vertices, clean.vertices);
+        assert_eq!(duplicated.indices, clean.indices);
+        assert!(stroke_polyline(&[a, a], &pen).vertices.is_empty());
+    }
+
+    #[test]
+    fn single_points_get_a_round_cap() {
+        let center = Vector::new(5.0, 5.0);
+        let pen = pen(3.0, LineJoin::Miter, LineCap::Round);
+
+        let dot = stroke_polyline(&[center, center], &pen);
+        assert!(!dot.indices.is_empty());
+        assert!((farthest(&dot, center) - (pen.half_width + pen.feather / 2.0)).abs() < 1e-4);
+
+        let covered: Vec<f32> = dot
+            .vertices
+            .iter()
+            .zip(&dot.coverage)
+            .filter(|(_, coverage)| **coverage > 0.0)
+            .map(|(vertex, _)| length(*vertex - center))
+            .collect();
+        assert!(covered
+            .iter()
+            .all(|distance| *distance <= pen.half_width - pen.feather / 2.0 + 1e-4));
+
+        for cap in [LineCap::Butt, LineCap::Square] {
+            let pen = Pen { cap, ..pen };
+            assert!(stroke_polyline(&[center], &pen).vertices.is_empty());
+        }
+    }
+
+    #[test]
+    fn thin_lines_fade_instead_of_vanishing() {
+        let pen = pen(0.25, LineJoin::Miter, LineCap::Butt);
+        let points = [Vector::new(0.0, 0.0), Vector::new(20.0, 0.0)];
+
+        let tessellation = stroke_polyline(&points, &pen);
+
+        // Half a pixel wide lines are drawn a pixel and a half wide, at half coverage.
+        let coverage = tessellation.coverage.iter().copied().fold(0.0, f32::max);
+        assert_eq!(coverage, 0.5);
+        let reach = tessellation
+            .vertices
+            .iter()
+            .map(|vertex| vertex.y.abs())
+            .fold(0.0, f32::max);
+        assert_eq!(reach, pen.half_width + pen.feather / 2.0);
+        assert!(!tessellation.indices.is_empty());
+    }
+}
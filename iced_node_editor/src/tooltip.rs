@@ -0,0 +1,93 @@
+use iced::advanced::{layout, mouse, renderer, widget, Layout};
+use iced::{Background, Color, Element, Point, Rectangle, Size, Vector};
+
+/// Distance from the cursor to the corner of a tooltip.
+const CURSOR_OFFSET: Vector = Vector::new(12.0, 16.0);
+const PADDING: f32 = 6.0;
+const BORDER_RADIUS: f32 = 4.0;
+
+/// Colors of the box a tooltip is drawn in.
+#[derive(Debug, Clone, Copy)]
+pub struct TooltipStyle {
+    pub(crate) background: Background,
+    pub(crate) border_color: Color,
+    pub(crate) text_color: Color,
+}
+
+/// Draws `content` in a box next to the cursor, in screen space so that it keeps its size
+/// whatever the zoom level, moved to the other side of the cursor or against the edges
+/// when it would leave `bounds`.
+pub(crate) fn draw<Message, Renderer>(
+    content: &Element<'_, Message, Renderer>,
+    tree: &widget::Tree,
+    renderer: &mut Renderer,
+    theme: &Renderer::Theme,
+    style: TooltipStyle,
+    cursor: Point,
+    bounds: Rectangle,
+) where
+    Renderer: renderer::Renderer,
+{
+    let limits = layout::Limits::new(
+        Size::ZERO,
+        Size::new(
+            (bounds.width - PADDING * 2.0).max(0.0),
+            (bounds.height - PADDING * 2.0).max(0.0),
+        ),
+    );
+    let mut node = content.as_widget().layout(renderer, &limits);
+    let size = Size::new(
+        node.size().width + PADDING * 2.0,
+        node.size().height + PADDING * 2.0,
+    );
+
+    let place = |cursor: f32, offset: f32, length: f32, start: f32, end: f32| {
+        let position = if cursor + offset + length > end {
+            cursor - offset - length
+        } else {
+            cursor + offset
+        };
+        position.min(end - length).max(start)
+    };
+    let position = Point::new(
+        place(
+            cursor.x,
+            CURSOR_OFFSET.x,
+            size.width,
+            bounds.x,
+            bounds.x + bounds.width,
+        ),
+        place(
+            cursor.y,
+            CURSOR_OFFSET.y,
+            size.height,
+            bounds.y,
+            bounds.y + bounds.height,
+        ),
+    );
+    node.move_to(position + Vector::new(PADDING, PADDING));
+
+    renderer.with_layer(bounds, |renderer| {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle::new(position, size),
+                border_radius: BORDER_RADIUS.into(),
+                border_width: 1.0,
+                border_color: style.border_color,
+            },
+            style.background,
+        );
+
+        content.as_widget().draw(
+            tree,
+            renderer,
+            theme,
+            &renderer::Style {
+                text_color: style.text_color,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &bounds,
+        );
+    });
+}